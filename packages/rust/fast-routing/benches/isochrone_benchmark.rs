@@ -0,0 +1,87 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use fast_routing::{ContractionHierarchy, DummyNetworkGenerator, IsochroneCalculator, IsochroneResult, RoutingMode, SearchMode};
+use rand::prelude::*;
+
+const TIME_THRESHOLDS: [f64; 4] = [300.0, 600.0, 900.0, 1200.0];
+const GRID_SIZES: [usize; 3] = [10, 25, 50];
+
+/// Isochrone query regression suite: network loading and CH construction
+/// happen once per grid size, outside every timed sample, so only
+/// `IsochroneCalculator::calculate` itself is measured. Parameterized over
+/// both network size (`GRID_SIZES`) and time threshold as separate criterion
+/// inputs, so a regression in either dimension shows up in its own series
+/// instead of being averaged away.
+fn isochrone_query_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("isochrone_query");
+
+    for &grid_size in &GRID_SIZES {
+        let network = DummyNetworkGenerator::create_grid(grid_size, grid_size, 100.0)
+            .expect("failed to build benchmark grid");
+        let ch = ContractionHierarchy::new(network, RoutingMode::Car)
+            .expect("failed to build contraction hierarchy");
+        let all_node_ids = ch.original_network.get_all_node_ids();
+
+        for &threshold in &TIME_THRESHOLDS {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{grid_size}x{grid_size}_grid"), threshold as u32),
+                &threshold,
+                |b, &threshold| {
+                    // Picking a random start node is itself nontrivial and
+                    // shouldn't count toward query latency, so it's drawn in
+                    // the untimed setup half of `iter_batched` -- playing the
+                    // same role other frameworks' `iter_with_large_setup`
+                    // helpers do.
+                    b.iter_batched(
+                        || *all_node_ids.choose(&mut thread_rng()).expect("grid has no nodes"),
+                        |start_node| {
+                            IsochroneCalculator::calculate(&ch, start_node, threshold, RoutingMode::Car, SearchMode::Exact)
+                        },
+                        BatchSize::SmallInput,
+                    );
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Opt-in benchmark for `IsochroneCalculator::export_as_geoparquet`, kept
+/// separate from `isochrone_query_benchmarks` so export cost never hides
+/// inside (or inflates) the query-latency numbers above.
+fn geoparquet_export_benchmark(c: &mut Criterion) {
+    let network = DummyNetworkGenerator::create_grid(25, 25, 100.0)
+        .expect("failed to build benchmark grid");
+    let ch = ContractionHierarchy::new(network, RoutingMode::Car)
+        .expect("failed to build contraction hierarchy");
+    let all_node_ids = ch.original_network.get_all_node_ids();
+
+    let results: Vec<(u64, Vec<IsochroneResult>)> = all_node_ids
+        .iter()
+        .take(50)
+        .map(|&start_node| {
+            let point_results = TIME_THRESHOLDS
+                .iter()
+                .filter_map(|&threshold| {
+                    IsochroneCalculator::calculate(&ch, start_node, threshold, RoutingMode::Car, SearchMode::Exact).ok()
+                })
+                .collect();
+            (start_node, point_results)
+        })
+        .collect();
+
+    c.bench_function("geoparquet_export", |b| {
+        b.iter_batched(
+            || std::env::temp_dir().join(format!("fast_routing_bench_export_{}.parquet", std::process::id())),
+            |path| {
+                IsochroneCalculator::export_as_geoparquet(&results, &ch.original_network, path.to_str().expect("non-utf8 temp path"))
+                    .expect("export failed");
+                let _ = std::fs::remove_file(&path);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(benches, isochrone_query_benchmarks, geoparquet_export_benchmark);
+criterion_main!(benches);
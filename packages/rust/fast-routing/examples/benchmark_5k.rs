@@ -1,6 +1,64 @@
 use fast_routing::{NetworkLoader, ContractionHierarchy, IsochroneCalculator, RoutingResult};
 use std::time::Instant;
+use std::collections::HashMap;
 use rand::prelude::*;
+use hdrhistogram::Histogram;
+use serde::Serialize;
+
+/// Latency percentiles for one time threshold's worth of
+/// `IsochroneCalculator::calculate` calls, as recorded into an HDR histogram
+/// (1µs min, ~60s max, 3 significant figures -- the precision latte-cli uses
+/// for query timing). Exposed as a field of `BenchmarkReport` so callers can
+/// serialize percentiles instead of only reading stdout.
+#[derive(Debug, Serialize)]
+struct LatencyPercentiles {
+    threshold_seconds: f64,
+    samples: u64,
+    mean_us: f64,
+    p50_us: f64,
+    p90_us: f64,
+    p99_us: f64,
+    p999_us: f64,
+    max_us: f64,
+}
+
+/// Structured summary of a benchmark run, built from the per-threshold
+/// latency histograms so the percentiles can be serialized (e.g. to JSON)
+/// instead of only being printed.
+#[derive(Debug, Serialize)]
+struct BenchmarkReport {
+    latencies_by_threshold: Vec<LatencyPercentiles>,
+}
+
+impl BenchmarkReport {
+    fn from_histograms(histograms: &HashMap<u32, Histogram<u64>>) -> Self {
+        let mut latencies_by_threshold: Vec<LatencyPercentiles> = histograms
+            .iter()
+            .map(|(&threshold, hist)| LatencyPercentiles {
+                threshold_seconds: threshold as f64,
+                samples: hist.len(),
+                mean_us: hist.mean(),
+                p50_us: hist.value_at_quantile(0.50) as f64,
+                p90_us: hist.value_at_quantile(0.90) as f64,
+                p99_us: hist.value_at_quantile(0.99) as f64,
+                p999_us: hist.value_at_quantile(0.999) as f64,
+                max_us: hist.max() as f64,
+            })
+            .collect();
+
+        latencies_by_threshold.sort_by(|a, b| a.threshold_seconds.partial_cmp(&b.threshold_seconds).unwrap());
+        Self { latencies_by_threshold }
+    }
+
+    fn print(&self) {
+        println!("\nLatency distribution (HDR histogram, per time threshold):");
+        for p in &self.latencies_by_threshold {
+            println!("  {:.0}s ({:.0}min), {} samples:", p.threshold_seconds, p.threshold_seconds / 60.0, p.samples);
+            println!("    mean {:.1}us  p50 {:.1}us  p90 {:.1}us  p99 {:.1}us  p999 {:.1}us  max {:.1}us",
+                    p.mean_us, p.p50_us, p.p90_us, p.p99_us, p.p999_us, p.max_us);
+        }
+    }
+}
 
 fn main() -> RoutingResult<()> {
     env_logger::init();
@@ -24,7 +82,7 @@ fn main() -> RoutingResult<()> {
     // Build contraction hierarchy
     println!("\\n2. Building contraction hierarchy...");
     let start_time = Instant::now();
-    let ch = ContractionHierarchy::new(network)?;
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Walking)?;
     let ch_time = start_time.elapsed();
     
     println!("Contraction hierarchy built ({:.2}s)", ch_time.as_secs_f64());
@@ -55,6 +113,11 @@ fn main() -> RoutingResult<()> {
     let mut all_results = Vec::new();
     let mut total_reachable_nodes = 0;
     let benchmark_start = Instant::now();
+
+    let mut latency_histograms: HashMap<u32, Histogram<u64>> = time_thresholds
+        .iter()
+        .map(|&threshold| (threshold as u32, Histogram::new_with_bounds(1, 60_000_000, 3).unwrap()))
+        .collect();
     
     for (i, &start_node) in test_points.iter().enumerate() {
         if i % 500 == 0 {
@@ -71,7 +134,14 @@ fn main() -> RoutingResult<()> {
         let mut point_results = Vec::new();
         
         for &max_cost in &time_thresholds {
-            match IsochroneCalculator::calculate(&ch, start_node, max_cost) {
+            let call_start = Instant::now();
+            let outcome = IsochroneCalculator::calculate(&ch, start_node, max_cost, fast_routing::RoutingMode::Walking, fast_routing::SearchMode::Exact);
+            let elapsed_us = call_start.elapsed().as_micros().max(1) as u64;
+            if let Some(hist) = latency_histograms.get_mut(&(max_cost as u32)) {
+                let _ = hist.record(elapsed_us);
+            }
+
+            match outcome {
                 Ok(result) => {
                     total_reachable_nodes += result.reachable_nodes;
                     point_results.push(result);
@@ -117,10 +187,13 @@ fn main() -> RoutingResult<()> {
         let max_nodes = threshold_results.iter().map(|r| r.reachable_nodes).max().unwrap_or(0);
         let min_nodes = threshold_results.iter().map(|r| r.reachable_nodes).min().unwrap_or(0);
         
-        println!("  {:.0}s ({:.0}min): avg {:.0} nodes, range {}-{}, total {}", 
+        println!("  {:.0}s ({:.0}min): avg {:.0} nodes, range {}-{}, total {}",
                 threshold, threshold/60.0, avg_nodes, min_nodes, max_nodes, total_nodes);
     }
-    
+
+    let benchmark_report = BenchmarkReport::from_histograms(&latency_histograms);
+    benchmark_report.print();
+
     // Export results
     println!("\\n5. Exporting benchmark results...");
     let output_path = "data/results/benchmark_walking_5k.parquet";
@@ -3,11 +3,44 @@ use std::time::{Duration, Instant};
 use rand::prelude::*;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
-use sysinfo::{System, Pid};
+use sysinfo::{Networks, System, Pid};
+
+/// Fires at most once per `interval_ms`, so one monitoring loop can sample
+/// different metrics at different cadences (memory every tick, CPU/disk/
+/// network less often) without a thread per metric. Modeled on solana's
+/// `AtomicInterval`.
+struct AtomicInterval {
+    last_fired_ms: AtomicU64,
+    interval_ms: u64,
+}
+
+impl AtomicInterval {
+    fn new(interval: Duration) -> Self {
+        Self {
+            last_fired_ms: AtomicU64::new(0),
+            interval_ms: interval.as_millis() as u64,
+        }
+    }
+
+    /// Returns `true` (and resets the timer) if `interval_ms` has elapsed
+    /// since the last time this returned `true`.
+    fn should_fire(&self, now_ms: u64) -> bool {
+        let last = self.last_fired_ms.load(Ordering::Relaxed);
+        if now_ms.saturating_sub(last) >= self.interval_ms {
+            self.last_fired_ms.store(now_ms, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+}
 
 struct ResourceMonitor {
     measurements: Arc<Mutex<Vec<ResourceMeasurement>>>,
+    running: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
 }
 
 #[derive(Debug, Clone)]
@@ -16,50 +49,103 @@ struct ResourceMeasurement {
     memory_mb: f64,
     cpu_percent: f64,
     system_memory_used_percent: f64,
+    disk_read_mb: f64,
+    disk_write_mb: f64,
+    network_rx_mb: f64,
+    network_tx_mb: f64,
 }
 
 impl ResourceMonitor {
     fn new() -> Self {
         Self {
             measurements: Arc::new(Mutex::new(Vec::new())),
+            running: Arc::new(AtomicBool::new(true)),
+            handle: None,
         }
     }
 
     fn start_monitoring(&mut self) -> Arc<Mutex<Vec<ResourceMeasurement>>> {
         let measurements = Arc::clone(&self.measurements);
+        let running = Arc::clone(&self.running);
         let pid = std::process::id();
-        
-        thread::spawn(move || {
+
+        let handle = thread::spawn(move || {
             let mut system = System::new_all();
+            let mut networks = Networks::new_with_refreshed_list();
             let start_time = Instant::now();
-            
-            loop {
+
+            // `refresh_all` runs every tick and memory and system-memory are
+            // recorded every tick; the CPU and disk readings are only taken, and
+            // the network counters only refreshed, on slower intervals, since they
+            // don't need 100ms resolution for a benchmark that runs for minutes.
+            let cpu_interval = AtomicInterval::new(Duration::from_millis(500));
+            let io_interval = AtomicInterval::new(Duration::from_millis(1000));
+
+            let mut cpu_percent = 0.0;
+            let mut disk_read_mb = 0.0;
+            let mut disk_write_mb = 0.0;
+            let mut network_rx_mb = 0.0;
+            let mut network_tx_mb = 0.0;
+
+            while running.load(Ordering::Relaxed) {
                 system.refresh_all();
-                
+                let now_ms = start_time.elapsed().as_millis() as u64;
+
                 if let Some(process) = system.process(Pid::from(pid as usize)) {
                     let timestamp = start_time.elapsed().as_secs_f64();
                     let memory_mb = process.memory() as f64 / 1024.0 / 1024.0;
-                    let cpu_percent = process.cpu_usage() as f64;
+
+                    if cpu_interval.should_fire(now_ms) {
+                        cpu_percent = process.cpu_usage() as f64;
+                    }
+
+                    if io_interval.should_fire(now_ms) {
+                        let disk_usage = process.disk_usage();
+                        disk_read_mb = disk_usage.total_read_bytes as f64 / 1024.0 / 1024.0;
+                        disk_write_mb = disk_usage.total_written_bytes as f64 / 1024.0 / 1024.0;
+
+                        networks.refresh();
+                        let (rx_bytes, tx_bytes) = networks.iter().fold((0u64, 0u64), |(rx, tx), (_, data)| {
+                            (rx + data.total_received(), tx + data.total_transmitted())
+                        });
+                        network_rx_mb = rx_bytes as f64 / 1024.0 / 1024.0;
+                        network_tx_mb = tx_bytes as f64 / 1024.0 / 1024.0;
+                    }
+
                     let system_memory_used_percent = (system.used_memory() as f64 / system.total_memory() as f64) * 100.0;
-                    
+
                     let measurement = ResourceMeasurement {
                         timestamp,
                         memory_mb,
                         cpu_percent,
                         system_memory_used_percent,
+                        disk_read_mb,
+                        disk_write_mb,
+                        network_rx_mb,
+                        network_tx_mb,
                     };
-                    
+
                     if let Ok(mut measurements) = measurements.lock() {
                         measurements.push(measurement);
                     }
                 }
-                
-                thread::sleep(Duration::from_millis(100)); // Sample every 100ms
+
+                thread::sleep(Duration::from_millis(100));
             }
         });
-        
+
+        self.handle = Some(handle);
         Arc::clone(&self.measurements)
     }
+
+    /// Signal the monitoring thread to stop and join it, so it doesn't
+    /// outlive the benchmark it was measuring.
+    fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
 }
 
 fn print_system_info() {
@@ -104,20 +190,34 @@ fn print_resource_stats(measurements: &[ResourceMeasurement]) {
     println!("\nSystem Memory Usage:");
     println!("  Peak: {:.1}%", system_memory_values.iter().fold(0.0f64, |a, &b| a.max(b)));
     println!("  Average: {:.1}%", system_memory_values.iter().sum::<f64>() / system_memory_values.len() as f64);
+
+    let first = measurements.first().unwrap();
+    let last = measurements.last().unwrap();
+    println!("\nProcess Disk I/O:");
+    println!("  Read: {:.2} MB", last.disk_read_mb - first.disk_read_mb);
+    println!("  Written: {:.2} MB", last.disk_write_mb - first.disk_write_mb);
+
+    println!("\nSystem Network I/O:");
+    println!("  Received: {:.2} MB", last.network_rx_mb - first.network_rx_mb);
+    println!("  Sent: {:.2} MB", last.network_tx_mb - first.network_tx_mb);
 }
 
 fn export_monitoring_csv(measurements: &[ResourceMeasurement], filename: &str) -> std::io::Result<()> {
     use std::io::Write;
     
     let mut file = std::fs::File::create(filename)?;
-    writeln!(file, "timestamp_seconds,process_memory_mb,process_cpu_percent,system_memory_percent")?;
-    
+    writeln!(file, "timestamp_seconds,process_memory_mb,process_cpu_percent,system_memory_percent,disk_read_mb,disk_write_mb,network_rx_mb,network_tx_mb")?;
+
     for measurement in measurements {
-        writeln!(file, "{:.3},{:.2},{:.2},{:.2}", 
+        writeln!(file, "{:.3},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
                 measurement.timestamp,
                 measurement.memory_mb,
                 measurement.cpu_percent,
-                measurement.system_memory_used_percent)?;
+                measurement.system_memory_used_percent,
+                measurement.disk_read_mb,
+                measurement.disk_write_mb,
+                measurement.network_rx_mb,
+                measurement.network_tx_mb)?;
     }
     
     println!("Exported monitoring data to {}", filename);
@@ -148,7 +248,11 @@ fn main() -> RoutingResult<()> {
     
     println!("\n2. Building contraction hierarchy...");
     let hierarchy_start = Instant::now();
-    let ch = ContractionHierarchy::new(network)?;
+    #[cfg(feature = "profiling")]
+    let ch_memory_region = fast_routing::profiling::Region::new();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Walking)?;
+    #[cfg(feature = "profiling")]
+    let ch_memory_stats = ch_memory_region.change();
     let hierarchy_time = hierarchy_start.elapsed();
     println!("Hierarchy built in {:.3}s", hierarchy_time.as_secs_f64());
     
@@ -163,41 +267,68 @@ fn main() -> RoutingResult<()> {
     
     println!("Selected {} starting points", test_points.len());
     
-    println!("\n4. Computing isochrones...");
+    println!("\n4. Computing isochrones (parallel via rayon)...");
     let computation_start = Instant::now();
-    
+    #[cfg(feature = "profiling")]
+    let isochrone_memory_region = fast_routing::profiling::Region::new();
+
     let time_thresholds = vec![300.0, 600.0, 900.0, 1200.0]; // 5, 10, 15, 20 minutes
-    
+
+    let progress_counter = Arc::new(AtomicUsize::new(0));
+    let progress_done = Arc::new(AtomicBool::new(false));
+    let progress_handle = {
+        let progress_counter = Arc::clone(&progress_counter);
+        let progress_done = Arc::clone(&progress_done);
+        let total_points = test_points.len();
+        thread::spawn(move || {
+            while !progress_done.load(Ordering::Relaxed) {
+                let done = progress_counter.load(Ordering::Relaxed);
+                let elapsed = computation_start.elapsed().as_secs_f64();
+                let rate = if elapsed > 0.0 { done as f64 / elapsed } else { 0.0 };
+                let eta = if rate > 0.0 { (total_points - done) as f64 / rate } else { 0.0 };
+
+                println!("   Progress: {}/{} ({:.1}%) - {:.1} points per sec - ETA: {:.1}s",
+                        done, total_points,
+                        (done as f64 / total_points as f64) * 100.0,
+                        rate, eta);
+
+                thread::sleep(Duration::from_millis(500));
+            }
+        })
+    };
+
+    let batch_results = IsochroneCalculator::calculate_batch(
+        &ch,
+        &test_points,
+        &time_thresholds,
+        fast_routing::RoutingMode::Walking,
+        fast_routing::SearchMode::Exact,
+        None,
+        Some(&progress_counter),
+    )?;
+
+    progress_done.store(true, Ordering::Relaxed);
+    progress_handle.join().expect("progress thread panicked");
+
     let mut all_results = Vec::new();
     let mut total_reachable = 0;
     let mut stats_by_time: HashMap<u32, (Vec<usize>, usize)> = HashMap::new();
-    
-    for (i, &start_node) in test_points.iter().enumerate() {
-        if i % 500 == 0 {
-            let elapsed = computation_start.elapsed().as_secs_f64();
-            let rate = if elapsed > 0.0 { i as f64 / elapsed } else { 0.0 };
-            let eta = if rate > 0.0 { (test_points.len() - i) as f64 / rate } else { 0.0 };
-            
-            println!("   Progress: {}/{} ({:.1}%) - {:.1} points per sec - ETA: {:.1}s", 
-                    i, test_points.len(), 
-                    (i as f64 / test_points.len() as f64) * 100.0,
-                    rate, eta);
-        }
-        
+
+    for (start_node, results) in batch_results {
         let mut point_results = Vec::new();
-        
-        for &max_cost in &time_thresholds {
-            match IsochroneCalculator::calculate(&ch, start_node, max_cost) {
+
+        for result in results {
+            match result {
                 Ok(result) => {
-                    let time_key = max_cost as u32;
+                    let time_key = result.max_cost as u32;
                     let reachable_count = result.reachable_nodes;
                     total_reachable += reachable_count;
-                    
+
                     stats_by_time.entry(time_key)
                         .or_insert_with(|| (Vec::new(), 0))
                         .0.push(reachable_count);
                     stats_by_time.get_mut(&time_key).unwrap().1 += reachable_count;
-                    
+
                     point_results.push(result);
                 },
                 Err(e) => {
@@ -205,14 +336,16 @@ fn main() -> RoutingResult<()> {
                 }
             }
         }
-        
+
         all_results.push((start_node, point_results));
     }
-    
+
     let computation_time = computation_start.elapsed();
-    
+    #[cfg(feature = "profiling")]
+    let isochrone_memory_stats = isochrone_memory_region.change();
+
     // Stop monitoring and get final measurements
-    thread::sleep(Duration::from_millis(200)); // Allow final measurements
+    monitor.stop();
     let final_measurements = {
         let measurements_guard = measurements.lock().unwrap();
         measurements_guard.clone()
@@ -254,7 +387,25 @@ fn main() -> RoutingResult<()> {
     println!("Hierarchy building: {:.2} seconds", hierarchy_time.as_secs_f64());
     println!("Isochrone calculation: {:.2} seconds", computation_time.as_secs_f64());
     println!("Total time: {:.2} seconds", (load_time + hierarchy_time + computation_time).as_secs_f64());
-    
+
+    #[cfg(feature = "profiling")]
+    {
+        let to_mb = |bytes: usize| bytes as f64 / 1024.0 / 1024.0;
+        println!("\nAllocator stats (profiling feature):");
+        println!(
+            "  Hierarchy build: {:.2} MB allocated, {:.2} MB deallocated, {:.2} MB peak",
+            to_mb(ch_memory_stats.bytes_allocated),
+            to_mb(ch_memory_stats.bytes_deallocated),
+            to_mb(ch_memory_stats.peak_bytes)
+        );
+        println!(
+            "  Isochrone computation: {:.2} MB allocated, {:.2} MB deallocated, {:.2} MB peak",
+            to_mb(isochrone_memory_stats.bytes_allocated),
+            to_mb(isochrone_memory_stats.bytes_deallocated),
+            to_mb(isochrone_memory_stats.peak_bytes)
+        );
+    }
+
     println!("\nOutput files:");
     println!("  - data/results/resource_monitoring_5k.csv (system monitoring data)");
     
@@ -74,7 +74,7 @@ fn main() -> RoutingResult<()> {
     for i in 1..=3 {
         println!("   Building CH iteration {}...", i);
         let start_time = Instant::now();
-        let _ch = ContractionHierarchy::new(network.clone())?;
+        let _ch = ContractionHierarchy::new(network.clone(), fast_routing::RoutingMode::Walking)?;
         let ch_time = start_time.elapsed();
         
         println!("     Built in {:.3}s", ch_time.as_secs_f64());
@@ -11,10 +11,10 @@ fn main() -> RoutingResult<()> {
              network.edge_count());
     
     println!("Building contraction hierarchy...");
-    let ch = ContractionHierarchy::new(network)?;
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Walking)?;
     
     println!("Calculating isochrone from node 0 within 300 time units...");
-    let isochrone_result = IsochroneCalculator::calculate(&ch, 0, 300.0)?;
+    let isochrone_result = IsochroneCalculator::calculate(&ch, 0, 300.0, fast_routing::RoutingMode::Walking, fast_routing::SearchMode::Exact)?;
     
     println!("Isochrone calculation completed!");
     println!("Maximum cost: {:.2}", isochrone_result.max_cost);
@@ -24,7 +24,7 @@ fn main() -> RoutingResult<()> {
     let test_nodes = vec![6, 12, 18, 24];
     for target in test_nodes {
         println!("\nTesting path from node 0 to node {}...", target);
-        match ch.shortest_path(0, target)? {
+        match ch.shortest_path(0, target, fast_routing::RoutingMode::Walking)? {
             Some((cost, path)) => {
                 println!("  Path found with cost {:.2}: {:?}", cost, path);
             }
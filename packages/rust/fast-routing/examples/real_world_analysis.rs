@@ -17,7 +17,7 @@ fn main() -> RoutingResult<()> {
     
     // Build contraction hierarchy
     println!("\n2. Building contraction hierarchy...");
-    let ch = ContractionHierarchy::new(network)?;
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Walking)?;
     let stats = ch.stats();
     
     println!("✓ Contraction hierarchy built");
@@ -47,7 +47,7 @@ fn main() -> RoutingResult<()> {
         let mut point_results = Vec::new();
         
         for &max_cost in &cost_thresholds {
-                match IsochroneCalculator::calculate(&ch, start_node, max_cost) {
+                match IsochroneCalculator::calculate(&ch, start_node, max_cost, fast_routing::RoutingMode::Walking, fast_routing::SearchMode::Exact) {
                 Ok(result) => {
                     println!("  {:.0}s ({:.1}min): {} reachable nodes", 
                             max_cost, max_cost/60.0,
@@ -90,7 +90,7 @@ fn main() -> RoutingResult<()> {
                 let from = test_points[i];
                 let to = test_points[j];
                 
-                match ch.shortest_path(from, to) {
+                match ch.shortest_path(from, to, fast_routing::RoutingMode::Walking) {
                     Ok(Some((cost, path))) => {
                         println!("  Path {} → {}: cost {:.2}s ({:.1}min), {} nodes", 
                                 from, to, cost, cost/60.0, path.len());
@@ -1,8 +1,18 @@
-use crate::{RoutingError, RoutingResult, network::{Network, NodeId, Cost}};
+use crate::{RoutingError, RoutingResult, network::{Network, NodeId, EdgeId, Cost, RoutingMode, PredecessorMode}};
+use crate::utils::Utils;
+use crate::tsp;
+use geo_types::Point;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
-use std::collections::{HashMap, BinaryHeap};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::{HashMap, HashSet, BinaryHeap};
 use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// Safety cap on 2-opt improvement passes in `ContractionHierarchy::optimize_order`,
+/// guarding against pathological inputs where improving swaps keep appearing.
+const MAX_TWO_OPT_ITERATIONS: usize = 100;
 
 /// State for Dijkstra search
 #[derive(Debug, Clone, PartialEq)]
@@ -25,32 +35,519 @@ impl PartialOrd for DijkstraState {
     }
 }
 
-/// Simplified Contraction Hierarchy (actually just uses Dijkstra for now)
+/// State for the rank-limited bidirectional CH search
+#[derive(Debug, Clone, PartialEq)]
+struct RankedState {
+    node: NodeId,
+    cost: Cost,
+}
+
+impl Eq for RankedState {}
+
+impl Ord for RankedState {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for RankedState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Contraction Hierarchy: nodes are contracted in ascending `rank` order,
+/// each contraction possibly inserting a shortcut edge over the pair of
+/// neighbors it connects. `shortcuts` records, for every shortcut `(from,
+/// to)`, the node it was contracted through, so a path found over the
+/// augmented graph can be unpacked back into real edges of `original_network`.
+///
+/// Preprocessing (and therefore the shortcuts and ranks) is specific to
+/// `mode`, since edge costs vary per mode. `shortest_path` still accepts a
+/// `mode` argument for API symmetry with the rest of the crate, but only
+/// uses the contracted search when it matches `self.mode`; other modes fall
+/// back to plain Dijkstra over `original_network`.
 #[derive(Debug)]
 pub struct ContractionHierarchy {
     /// Original network
     pub original_network: Network,
+    /// Routing mode this hierarchy was contracted for
+    pub mode: RoutingMode,
+    /// Contraction rank of each node (lower = contracted earlier)
+    rank: HashMap<NodeId, usize>,
+    /// Undirected adjacency of the augmented graph (original edges for
+    /// `mode` plus every shortcut inserted during contraction)
+    augmented_adj: HashMap<NodeId, Vec<(NodeId, Cost)>>,
+    /// For each shortcut `(from, to)`, the node it was contracted through
+    shortcuts: HashMap<(NodeId, NodeId), NodeId>,
+}
+
+/// On-disk form of a `ContractionHierarchy`, written by `save_to_path` and
+/// validated by `load_from_path`. `shortcuts` is stored as a flat list since
+/// tuple keys don't round-trip through JSON object keys.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedHierarchy {
+    /// Digest of the network this hierarchy was built from; see
+    /// `ContractionHierarchy::network_digest`.
+    network_digest: String,
+    rank: HashMap<NodeId, usize>,
+    augmented_adj: HashMap<NodeId, Vec<(NodeId, Cost)>>,
+    shortcuts: Vec<((NodeId, NodeId), NodeId)>,
 }
 
 impl ContractionHierarchy {
-    /// Build contraction hierarchy from network (simplified)
-    pub fn new(network: Network) -> RoutingResult<Self> {
+    /// Build a contraction hierarchy for `mode`: order nodes by an
+    /// edge-difference heuristic, then contract them in that order,
+    /// inserting a shortcut between two uncontracted neighbors whenever
+    /// going through the contracted node is strictly cheaper than any direct edge
+    /// already known between them.
+    ///
+    /// Warns via `log` when the network is fragmented into multiple
+    /// connected components (for `mode`), since isochrones/paths starting in
+    /// a small stranded component will silently look incomplete.
+    pub fn new(network: Network, mode: RoutingMode) -> RoutingResult<Self> {
+        let components = network.connected_components(mode);
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for &component_id in components.values() {
+            *sizes.entry(component_id).or_insert(0) += 1;
+        }
+
+        if sizes.len() > 1 {
+            let largest = sizes.values().max().copied().unwrap_or(0);
+            let stranded = network.node_count() - largest;
+            log::warn!(
+                "Network is fragmented into {} connected components; {} of {} nodes are outside the largest component",
+                sizes.len(), stranded, network.node_count()
+            );
+        }
+
+        let (rank, augmented_adj, shortcuts) = Self::contract(&network, mode);
+
         Ok(Self {
             original_network: network,
+            mode,
+            rank,
+            augmented_adj,
+            shortcuts,
         })
     }
 
+    /// Load a hierarchy for `mode` from a cache previously written by
+    /// `save_to_path` under `dir`, if one exists and its stored digest
+    /// matches `network`'s current content; otherwise contract `network`
+    /// from scratch via `new` and write the result back to `dir` for next
+    /// time.
+    pub fn load_from_path(dir: &Path, network: Network, mode: RoutingMode) -> RoutingResult<Self> {
+        Self::load_or_build(dir, Self::network_digest(&network, mode), network, mode)
+    }
+
+    /// Load a hierarchy for `mode` from a cache under `dir`, keyed by the
+    /// SHA3-256 digest of `parquet_path`'s raw bytes rather than
+    /// `network_digest`'s derived network content.
+    ///
+    /// Prefer this over `load_from_path` when the original source file is
+    /// still around: hashing its bytes directly is cheaper than re-deriving
+    /// a canonical digest from the already-loaded `Network`, and also
+    /// catches changes `network_digest` wouldn't notice, like a re-export
+    /// that reorders rows without changing any node or edge.
+    pub fn load_or_build_from_parquet(
+        dir: &Path,
+        parquet_path: &str,
+        network: Network,
+        mode: RoutingMode,
+    ) -> RoutingResult<Self> {
+        let digest = Self::parquet_digest(parquet_path)?;
+        Self::load_or_build(dir, digest, network, mode)
+    }
+
+    /// Shared body of `load_from_path` / `load_or_build_from_parquet`: serve
+    /// `dir`'s cache for `mode` if its stored digest matches
+    /// `expected_digest`, otherwise contract `network` from scratch and
+    /// write the result back under that digest.
+    fn load_or_build(dir: &Path, expected_digest: String, network: Network, mode: RoutingMode) -> RoutingResult<Self> {
+        if let Some(cached) = Self::read_cache(dir, mode) {
+            if cached.network_digest == expected_digest {
+                return Ok(Self {
+                    original_network: network,
+                    mode,
+                    rank: cached.rank,
+                    augmented_adj: cached.augmented_adj,
+                    shortcuts: cached.shortcuts.into_iter().collect(),
+                });
+            }
+        }
+
+        let ch = Self::new(network, mode)?;
+        ch.save_to_path_with_digest(dir, &expected_digest)?;
+        Ok(ch)
+    }
+
+    /// Serialize this hierarchy's rank, shortcuts and augmented adjacency to
+    /// `<dir>/ch_<mode>.json`, tagged with a digest of the network it was
+    /// built from so a later `load_from_path` can tell a stale cache from a
+    /// fresh one.
+    pub fn save_to_path(&self, dir: &Path) -> RoutingResult<()> {
+        let digest = Self::network_digest(&self.original_network, self.mode);
+        self.save_to_path_with_digest(dir, &digest)
+    }
+
+    /// `save_to_path`, but tagging the cache file with a caller-supplied
+    /// digest instead of always deriving `network_digest` -- used by
+    /// `load_or_build_from_parquet` to tag it with the source file's digest
+    /// instead.
+    fn save_to_path_with_digest(&self, dir: &Path, digest: &str) -> RoutingResult<()> {
+        std::fs::create_dir_all(dir)?;
+
+        let cached = CachedHierarchy {
+            network_digest: digest.to_string(),
+            rank: self.rank.clone(),
+            augmented_adj: self.augmented_adj.clone(),
+            shortcuts: self.shortcuts.iter().map(|(&k, &v)| (k, v)).collect(),
+        };
+
+        let json = serde_json::to_string(&cached)?;
+        std::fs::write(Self::cache_path(dir, self.mode), json)?;
+        Ok(())
+    }
+
+    /// SHA3-256 digest of `parquet_path`'s raw bytes, hex-encoded to match
+    /// `network_digest`'s format.
+    fn parquet_digest(parquet_path: &str) -> RoutingResult<String> {
+        let bytes = std::fs::read(parquet_path)?;
+        let mut hasher = Sha3_256::new();
+        hasher.update(&bytes);
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Read and deserialize the cache file for `mode` under `dir`, if it
+    /// exists and parses; any missing file or corrupt contents is treated as
+    /// a cold cache rather than an error, so a bad file just triggers a
+    /// rebuild.
+    fn read_cache(dir: &Path, mode: RoutingMode) -> Option<CachedHierarchy> {
+        let json = std::fs::read_to_string(Self::cache_path(dir, mode)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Path of the cache file for `mode` under `dir`.
+    fn cache_path(dir: &Path, mode: RoutingMode) -> PathBuf {
+        dir.join(format!("ch_{:?}.json", mode).to_lowercase())
+    }
+
+    /// SHA3-256 digest over the canonical content of `network` for `mode`:
+    /// sorted node ids/locations and edge ids/endpoints/mode-costs, so the
+    /// same network content always hashes the same way regardless of
+    /// `HashMap` iteration order.
+    fn network_digest(network: &Network, mode: RoutingMode) -> String {
+        let mut hasher = Sha3_256::new();
+        hasher.update(format!("{:?}", mode).as_bytes());
+
+        let mut node_ids: Vec<&NodeId> = network.nodes.keys().collect();
+        node_ids.sort();
+        for &id in node_ids {
+            let node = &network.nodes[&id];
+            hasher.update(id.to_le_bytes());
+            hasher.update(node.location.x().to_le_bytes());
+            hasher.update(node.location.y().to_le_bytes());
+        }
+
+        let mut edge_ids: Vec<&EdgeId> = network.edges.keys().collect();
+        edge_ids.sort();
+        for &id in edge_ids {
+            let edge = &network.edges[&id];
+            hasher.update(id.to_le_bytes());
+            hasher.update(edge.source.to_le_bytes());
+            hasher.update(edge.target.to_le_bytes());
+            if let Some(cost) = edge.get_cost(mode) {
+                hasher.update(cost.to_le_bytes());
+            }
+        }
+
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Run the contraction itself: build the mode-filtered adjacency list,
+    /// order nodes by edge-difference (ascending, so "simplest" nodes
+    /// contract first), then contract each node in turn.
+    fn contract(
+        network: &Network,
+        mode: RoutingMode,
+    ) -> (HashMap<NodeId, usize>, HashMap<NodeId, Vec<(NodeId, Cost)>>, HashMap<(NodeId, NodeId), NodeId>) {
+        let mut adj: HashMap<NodeId, Vec<(NodeId, Cost)>> = HashMap::new();
+        for edge in network.edges.values() {
+            if !edge.is_accessible(mode) {
+                continue;
+            }
+            let Some(cost) = edge.get_cost(mode) else { continue };
+            adj.entry(edge.source).or_default().push((edge.target, cost));
+            adj.entry(edge.target).or_default().push((edge.source, cost));
+        }
+        for node_id in network.nodes.keys() {
+            adj.entry(*node_id).or_default();
+        }
+
+        let mut order: Vec<NodeId> = adj.keys().copied().collect();
+        order.sort_by_key(|&node| Self::edge_difference(&adj, node));
+
+        let mut rank = HashMap::with_capacity(order.len());
+        let mut shortcuts: HashMap<(NodeId, NodeId), NodeId> = HashMap::new();
+        let mut contracted: HashSet<NodeId> = HashSet::with_capacity(order.len());
+
+        for (idx, &node) in order.iter().enumerate() {
+            rank.insert(node, idx);
+
+            let neighbors: Vec<(NodeId, Cost)> = adj.get(&node)
+                .map(|edges| edges.iter().filter(|(n, _)| !contracted.contains(n)).cloned().collect())
+                .unwrap_or_default();
+
+            for i in 0..neighbors.len() {
+                for j in (i + 1)..neighbors.len() {
+                    let (u, cost_u) = neighbors[i];
+                    let (w, cost_w) = neighbors[j];
+                    let via_cost = cost_u + cost_w;
+
+                    let existing = Self::direct_cost(&adj, u, w);
+                    if existing.map_or(true, |best| via_cost < best) {
+                        adj.entry(u).or_default().push((w, via_cost));
+                        adj.entry(w).or_default().push((u, via_cost));
+                        shortcuts.insert((u, w), node);
+                        shortcuts.insert((w, u), node);
+                    }
+                }
+            }
+
+            contracted.insert(node);
+        }
+
+        (rank, adj, shortcuts)
+    }
+
+    /// Edge-difference heuristic used to order contraction: nodes with fewer
+    /// potential shortcuts relative to their degree are contracted first.
+    /// `potential_shortcuts` is the upper bound `degree * (degree - 1) / 2`
+    /// (every neighbor pair might need one) rather than a simulated count,
+    /// which keeps ordering a single cheap pass instead of re-simulating
+    /// every node after each contraction.
+    fn edge_difference(adj: &HashMap<NodeId, Vec<(NodeId, Cost)>>, node: NodeId) -> i64 {
+        let degree = adj.get(&node).map_or(0, |edges| edges.len()) as i64;
+        let potential_shortcuts = degree * (degree - 1) / 2;
+        potential_shortcuts - degree
+    }
+
+    /// Cheapest already-known direct cost between `u` and `w`, if any.
+    fn direct_cost(adj: &HashMap<NodeId, Vec<(NodeId, Cost)>>, u: NodeId, w: NodeId) -> Option<Cost> {
+        adj.get(&u)?
+            .iter()
+            .filter(|(n, _)| *n == w)
+            .map(|(_, cost)| *cost)
+            .fold(None, |best, cost| Some(best.map_or(cost, |b: Cost| b.min(cost))))
+    }
+
+    /// Recursively unpack the edge `(from, to)` into real node ids, expanding
+    /// shortcuts into the two hops they replaced until only original edges
+    /// remain.
+    fn unpack_edge(&self, from: NodeId, to: NodeId, path: &mut Vec<NodeId>) {
+        if let Some(&via) = self.shortcuts.get(&(from, to)) {
+            self.unpack_edge(from, via, path);
+            self.unpack_edge(via, to, path);
+        } else {
+            path.push(to);
+        }
+    }
+
+    /// Rank-limited bidirectional Dijkstra over the augmented graph: the
+    /// forward search from `from` and backward search from `to` each only
+    /// relax edges toward higher-ranked nodes, guaranteeing they meet at the
+    /// highest-ranked node on the shortest path. The raw meeting-point path
+    /// is then unpacked back into original node ids via `unpack_edge`.
+    fn ch_search(&self, from: NodeId, to: NodeId) -> Option<(Cost, Vec<NodeId>)> {
+        let rank_of = |n: &NodeId| self.rank.get(n).copied().unwrap_or(usize::MAX);
+
+        let mut forward_dist: HashMap<NodeId, Cost> = HashMap::new();
+        let mut forward_pred: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut forward_heap = BinaryHeap::new();
+        forward_dist.insert(from, 0.0);
+        forward_heap.push(RankedState { node: from, cost: 0.0 });
+
+        let mut backward_dist: HashMap<NodeId, Cost> = HashMap::new();
+        let mut backward_pred: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut backward_heap = BinaryHeap::new();
+        backward_dist.insert(to, 0.0);
+        backward_heap.push(RankedState { node: to, cost: 0.0 });
+
+        let mut best_cost = Cost::INFINITY;
+        let mut best_meeting: Option<NodeId> = None;
+
+        while !forward_heap.is_empty() || !backward_heap.is_empty() {
+            if let Some(RankedState { node, cost }) = forward_heap.pop() {
+                if cost <= *forward_dist.get(&node).unwrap_or(&Cost::INFINITY) {
+                    if let Some(&backward_cost) = backward_dist.get(&node) {
+                        if cost + backward_cost < best_cost {
+                            best_cost = cost + backward_cost;
+                            best_meeting = Some(node);
+                        }
+                    }
+
+                    for &(neighbor, edge_cost) in self.augmented_adj.get(&node).into_iter().flatten() {
+                        if rank_of(&neighbor) <= rank_of(&node) {
+                            continue;
+                        }
+                        let new_cost = cost + edge_cost;
+                        if new_cost < *forward_dist.get(&neighbor).unwrap_or(&Cost::INFINITY) {
+                            forward_dist.insert(neighbor, new_cost);
+                            forward_pred.insert(neighbor, node);
+                            forward_heap.push(RankedState { node: neighbor, cost: new_cost });
+                        }
+                    }
+                }
+            }
+
+            if let Some(RankedState { node, cost }) = backward_heap.pop() {
+                if cost <= *backward_dist.get(&node).unwrap_or(&Cost::INFINITY) {
+                    if let Some(&forward_cost) = forward_dist.get(&node) {
+                        if cost + forward_cost < best_cost {
+                            best_cost = cost + forward_cost;
+                            best_meeting = Some(node);
+                        }
+                    }
+
+                    for &(neighbor, edge_cost) in self.augmented_adj.get(&node).into_iter().flatten() {
+                        if rank_of(&neighbor) <= rank_of(&node) {
+                            continue;
+                        }
+                        let new_cost = cost + edge_cost;
+                        if new_cost < *backward_dist.get(&neighbor).unwrap_or(&Cost::INFINITY) {
+                            backward_dist.insert(neighbor, new_cost);
+                            backward_pred.insert(neighbor, node);
+                            backward_heap.push(RankedState { node: neighbor, cost: new_cost });
+                        }
+                    }
+                }
+            }
+        }
+
+        let meeting = best_meeting?;
+
+        let mut forward_path = vec![from];
+        {
+            let mut current = meeting;
+            let mut hops = Vec::new();
+            while let Some(&pred) = forward_pred.get(&current) {
+                hops.push(current);
+                current = pred;
+            }
+            hops.reverse();
+            for hop in hops {
+                let prev = *forward_path.last().unwrap();
+                self.unpack_edge(prev, hop, &mut forward_path);
+            }
+        }
+
+        let mut backward_hops = vec![meeting];
+        {
+            let mut current = meeting;
+            while let Some(&pred) = backward_pred.get(&current) {
+                backward_hops.push(pred);
+                current = pred;
+            }
+        }
+        for pair in backward_hops.windows(2) {
+            let prev = *forward_path.last().unwrap();
+            self.unpack_edge(prev, pair[1], &mut forward_path);
+        }
+
+        Some((best_cost, forward_path))
+    }
+
+    /// Upward-only Dijkstra over the augmented graph from `start`: like one
+    /// direction of `ch_search`'s bidirectional sweep (only follow an edge to
+    /// a higher-ranked neighbor), but run to exhaustion instead of stopping
+    /// at a meeting point, so the caller gets every node this rank-limited
+    /// search settles along with its distance from `start`.
+    ///
+    /// `augmented_adj` is undirected, so this is used for both directions of
+    /// `RoutingMatrix::compute`'s bucket algorithm -- a "backward" sweep from
+    /// a target settles the same way a "forward" sweep from a source does.
+    pub(crate) fn upward_settle(&self, start: NodeId) -> HashMap<NodeId, Cost> {
+        let rank_of = |n: &NodeId| self.rank.get(n).copied().unwrap_or(usize::MAX);
+
+        let mut dist: HashMap<NodeId, Cost> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        dist.insert(start, 0.0);
+        heap.push(RankedState { node: start, cost: 0.0 });
+
+        while let Some(RankedState { node, cost }) = heap.pop() {
+            if cost > *dist.get(&node).unwrap_or(&Cost::INFINITY) {
+                continue;
+            }
+
+            for &(neighbor, edge_cost) in self.augmented_adj.get(&node).into_iter().flatten() {
+                if rank_of(&neighbor) <= rank_of(&node) {
+                    continue;
+                }
+                let new_cost = cost + edge_cost;
+                if new_cost < *dist.get(&neighbor).unwrap_or(&Cost::INFINITY) {
+                    dist.insert(neighbor, new_cost);
+                    heap.push(RankedState { node: neighbor, cost: new_cost });
+                }
+            }
+        }
+
+        dist
+    }
+
     /// Get stats about the hierarchy
     pub fn stats(&self) -> HashMap<String, String> {
         let mut stats = HashMap::new();
         stats.insert("nodes".to_string(), self.original_network.node_count().to_string());
         stats.insert("edges".to_string(), self.original_network.edge_count().to_string());
-        stats.insert("algorithm".to_string(), "Dijkstra".to_string());
+        stats.insert("algorithm".to_string(), "ContractionHierarchy".to_string());
+        stats.insert("shortcuts".to_string(), self.shortcuts.len().to_string());
         stats
     }
 
-    /// Perform shortest path search using basic Dijkstra
-    pub fn shortest_path(&self, from_node_id: NodeId, to_node_id: NodeId) -> RoutingResult<Option<(Cost, Vec<NodeId>)>> {
+    /// Snap a raw GPS coordinate to the closest network node, using
+    /// `original_network`'s R-tree spatial index.
+    ///
+    /// Returns the node id alongside its Haversine distance from
+    /// `(lon, lat)` in meters, so a caller can reject a snap that's
+    /// implausibly far from the network (e.g. a bad geocode) instead of
+    /// silently routing from the wrong place.
+    pub fn nearest_node(&self, lon: f64, lat: f64) -> Option<(NodeId, f64)> {
+        let point = Point::new(lon, lat);
+        let node_id = self.original_network.nearest_node(&point)?;
+        let node = self.original_network.get_node(node_id)?;
+        let distance = Utils::haversine_distance(&point, &node.location);
+        Some((node_id, distance))
+    }
+
+    /// Batch form of `nearest_node`, snapping every `(lon, lat)` in `points`
+    /// independently; a point with no snap (empty network) becomes `None` at
+    /// the same index rather than shortening the result.
+    pub fn snap_all(&self, points: &[(f64, f64)]) -> Vec<Option<(NodeId, f64)>> {
+        points.iter().map(|&(lon, lat)| self.nearest_node(lon, lat)).collect()
+    }
+
+    /// Perform a shortest path search between two node ids.
+    ///
+    /// When `mode` matches the mode this hierarchy was contracted for, the
+    /// query runs over the pre-built augmented graph (rank-limited
+    /// bidirectional Dijkstra with shortcut unpacking). Otherwise it falls
+    /// back to plain Dijkstra over `original_network`, since the hierarchy's
+    /// shortcuts are not valid for a different mode's costs.
+    pub fn shortest_path(&self, from_node_id: NodeId, to_node_id: NodeId, mode: RoutingMode) -> RoutingResult<Option<(Cost, Vec<NodeId>)>> {
+        if mode == self.mode {
+            if !self.augmented_adj.contains_key(&from_node_id) {
+                return Err(RoutingError::Network(format!("Node {} not found", from_node_id)));
+            }
+            if !self.augmented_adj.contains_key(&to_node_id) {
+                return Err(RoutingError::Network(format!("Node {} not found", to_node_id)));
+            }
+            if from_node_id == to_node_id {
+                return Ok(Some((0.0, vec![from_node_id])));
+            }
+            return Ok(self.ch_search(from_node_id, to_node_id));
+        }
+
         // Find node indices in original network
         let from_idx = self.original_network.get_node_index(from_node_id)
             .ok_or_else(|| RoutingError::Network(format!("Node {} not found", from_node_id)))?;
@@ -58,7 +555,7 @@ impl ContractionHierarchy {
             .ok_or_else(|| RoutingError::Network(format!("Node {} not found", to_node_id)))?;
 
         // Simple Dijkstra
-        self.dijkstra_search(from_idx, to_idx)
+        self.dijkstra_search(from_idx, to_idx, mode)
     }
 
     /// Simple Dijkstra implementation
@@ -66,6 +563,7 @@ impl ContractionHierarchy {
         &self,
         from_idx: NodeIndex,
         to_idx: NodeIndex,
+        mode: RoutingMode,
     ) -> RoutingResult<Option<(Cost, Vec<NodeId>)>> {
         let mut distances = HashMap::new();
         let mut predecessors = HashMap::new();
@@ -101,7 +599,7 @@ impl ContractionHierarchy {
 
             for edge in self.original_network.graph.edges(node) {
                 let neighbor = edge.target();
-                let edge_cost = edge.weight().cost;
+                let Some(edge_cost) = self.original_network.edge_cost(edge.weight().edge_id, mode) else { continue };
                 let new_cost = cost + edge_cost;
 
                 if new_cost < *distances.get(&neighbor).unwrap_or(&f64::INFINITY) {
@@ -115,12 +613,186 @@ impl ContractionHierarchy {
         Ok(None)
     }
 
+    /// Shortest path search using A* instead of plain Dijkstra. Delegates to
+    /// `Network::astar_search`, the crate's one bidirectional-A*
+    /// implementation, rather than keeping a second, unidirectional copy
+    /// here. Always returns the same optimal cost and path as
+    /// `shortest_path`, just expanding fewer nodes along the way for
+    /// geographically spread-out queries.
+    pub fn shortest_path_astar(&self, from_node_id: NodeId, to_node_id: NodeId, mode: RoutingMode) -> RoutingResult<Option<(Cost, Vec<NodeId>)>> {
+        self.original_network.astar_search(from_node_id, to_node_id, mode, PredecessorMode::Full)
+    }
+
     /// Get network statistics (detailed version)
     pub fn detailed_stats(&self) -> HashMap<String, usize> {
         let mut stats = HashMap::new();
         stats.insert("original_nodes".to_string(), self.original_network.node_count());
         stats.insert("original_edges".to_string(), self.original_network.edge_count());
-        stats.insert("shortcuts_created".to_string(), 0); // Simplified version
+        stats.insert("shortcuts_created".to_string(), self.shortcuts.len() / 2);
         stats
     }
+
+    /// Route through an ordered list of waypoints, chaining shortest paths
+    /// between consecutive stops.
+    ///
+    /// Returns the total cost and the concatenated path (shared nodes at
+    /// waypoint boundaries are not duplicated).
+    pub fn route_through(&self, waypoints: &[NodeId], mode: RoutingMode) -> RoutingResult<(Cost, Vec<NodeId>)> {
+        if waypoints.len() < 2 {
+            return Err(RoutingError::ContractionHierarchy(
+                "route_through requires at least 2 waypoints".to_string(),
+            ));
+        }
+
+        let mut total_cost = 0.0;
+        let mut full_path: Vec<NodeId> = Vec::new();
+
+        for pair in waypoints.windows(2) {
+            let (cost, path) = self.shortest_path(pair[0], pair[1], mode)?.ok_or_else(|| {
+                RoutingError::ContractionHierarchy(format!(
+                    "No path found between waypoints {} and {}",
+                    pair[0], pair[1]
+                ))
+            })?;
+
+            total_cost += cost;
+            if full_path.last() == path.first() {
+                full_path.extend_from_slice(&path[1..]);
+            } else {
+                full_path.extend_from_slice(&path);
+            }
+        }
+
+        Ok((total_cost, full_path))
+    }
+
+    /// Build an all-pairs cost matrix between waypoints using `shortest_path`.
+    fn waypoint_cost_matrix(&self, waypoints: &[NodeId], mode: RoutingMode) -> RoutingResult<Vec<Vec<Cost>>> {
+        let n = waypoints.len();
+        let mut matrix = vec![vec![0.0; n]; n];
+
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let (cost, _) = self.shortest_path(waypoints[i], waypoints[j], mode)?.ok_or_else(|| {
+                    RoutingError::ContractionHierarchy(format!(
+                        "No path found between waypoints {} and {}",
+                        waypoints[i], waypoints[j]
+                    ))
+                })?;
+                matrix[i][j] = cost;
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Cost of `shortest_path(from, stops[i], mode)` for every `i`, used to
+    /// fold a fixed endpoint into the TSP cost matrix alongside
+    /// `waypoint_cost_matrix`.
+    fn costs_from(&self, from: NodeId, stops: &[NodeId], mode: RoutingMode) -> RoutingResult<Vec<Cost>> {
+        stops.iter().map(|&stop| {
+            let (cost, _) = self.shortest_path(from, stop, mode)?.ok_or_else(|| {
+                RoutingError::ContractionHierarchy(format!("No path found between waypoints {} and {}", from, stop))
+            })?;
+            Ok(cost)
+        }).collect()
+    }
+
+    /// Cost of `shortest_path(stops[i], to, mode)` for every `i`, the mirror
+    /// of `costs_from`.
+    fn costs_to(&self, stops: &[NodeId], to: NodeId, mode: RoutingMode) -> RoutingResult<Vec<Cost>> {
+        stops.iter().map(|&stop| {
+            let (cost, _) = self.shortest_path(stop, to, mode)?.ok_or_else(|| {
+                RoutingError::ContractionHierarchy(format!("No path found between waypoints {} and {}", stop, to))
+            })?;
+            Ok(cost)
+        }).collect()
+    }
+
+    /// Solve for the minimum-cost order to visit `stops`, starting at `start`
+    /// and ending at `end`.
+    ///
+    /// The cost of entering the first stop from `start` and leaving the last
+    /// stop for `end` is folded into the matrix the permutation/2-opt search
+    /// optimizes over (via `costs_from`/`costs_to`), so the chosen order
+    /// actually minimizes the full `start` -> stops -> `end` cost rather than
+    /// just the cost of visiting `stops` in isolation.
+    ///
+    /// For up to 10 stops, all permutations are enumerated exhaustively. For
+    /// larger sets, a nearest-neighbor tour (seeded by distance from `start`)
+    /// is built and then improved with 2-opt, capped at
+    /// `MAX_TWO_OPT_ITERATIONS` full passes. Returns the reordered waypoints
+    /// (including `start` and `end`) together with the concatenated full path
+    /// and total cost.
+    pub fn optimize_order(
+        &self,
+        start: NodeId,
+        stops: &[NodeId],
+        end: NodeId,
+        mode: RoutingMode,
+    ) -> RoutingResult<(Vec<NodeId>, Cost, Vec<NodeId>)> {
+        if stops.is_empty() {
+            let (cost, path) = self.route_through(&[start, end], mode)?;
+            return Ok((vec![start, end], cost, path));
+        }
+
+        let matrix = self.waypoint_cost_matrix(stops, mode)?;
+        let entry_costs = self.costs_from(start, stops, mode)?;
+        let exit_costs = self.costs_to(stops, end, mode)?;
+
+        let order: Vec<usize> = if stops.len() <= 10 {
+            tsp::best_order_exhaustive(stops.len(), &matrix, Some(&entry_costs), Some(&exit_costs), false)
+        } else {
+            let mut order = tsp::nearest_neighbor_order(stops.len(), &matrix, Some(&entry_costs));
+            tsp::two_opt_improve(&mut order, &matrix, Some(&entry_costs), Some(&exit_costs), false, MAX_TWO_OPT_ITERATIONS);
+            order
+        };
+
+        let waypoint_order: Vec<NodeId> = order.iter().map(|&i| stops[i]).collect();
+
+        let mut full_sequence = Vec::with_capacity(waypoint_order.len() + 2);
+        full_sequence.push(start);
+        full_sequence.extend_from_slice(&waypoint_order);
+        full_sequence.push(end);
+
+        let (cost, path) = self.route_through(&full_sequence, mode)?;
+        Ok((full_sequence, cost, path))
+    }
+}
+
+/// Caches one `ContractionHierarchy` per `RoutingMode` over a single loaded
+/// `Network`, so walking/cycling/car/wheelchair queries share one graph and
+/// one spatial index instead of requiring four separately-loaded networks.
+#[derive(Debug)]
+pub struct MultiModalRouter {
+    network: Network,
+    hierarchies: HashMap<RoutingMode, ContractionHierarchy>,
+}
+
+impl MultiModalRouter {
+    /// Wrap a network without building any hierarchy yet; hierarchies are
+    /// built lazily per mode on first use.
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            hierarchies: HashMap::new(),
+        }
+    }
+
+    /// Get (building and caching if needed) the hierarchy for `mode`.
+    pub fn hierarchy_for(&mut self, mode: RoutingMode) -> RoutingResult<&ContractionHierarchy> {
+        if !self.hierarchies.contains_key(&mode) {
+            let ch = ContractionHierarchy::new(self.network.clone(), mode)?;
+            self.hierarchies.insert(mode, ch);
+        }
+        Ok(self.hierarchies.get(&mode).unwrap())
+    }
+
+    /// Access the shared underlying network (e.g. for its spatial index).
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
 }
\ No newline at end of file
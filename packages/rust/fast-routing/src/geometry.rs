@@ -0,0 +1,145 @@
+use geo_types::{Coord, LineString};
+use geojson::{Feature, FeatureCollection, Geometry, Value};
+use crate::{RoutingError, RoutingResult};
+use crate::network::{Network, NodeId};
+use crate::isochrone::IsochroneResult;
+
+/// Stitched geometry for a route, produced from the edges crossed along a path
+#[derive(Debug, Clone)]
+pub struct RouteGeometry {
+    pub linestring: LineString<f64>,
+    pub total_length: f64,
+}
+
+impl RouteGeometry {
+    /// Build a `RouteGeometry` by stitching each edge's stored `LineString`
+    /// together in traversal order, reversing an edge's geometry when the
+    /// path walks it target-to-source.
+    pub fn from_path(network: &Network, path: &[NodeId]) -> RoutingResult<Self> {
+        if path.len() < 2 {
+            return Err(RoutingError::Geometry(
+                "Path must contain at least 2 nodes to build geometry".to_string(),
+            ));
+        }
+
+        let mut coords: Vec<Coord<f64>> = Vec::new();
+        let mut total_length = 0.0;
+
+        for pair in path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let (edge, forward) = Self::find_edge_between(network, from, to)?;
+
+            let mut segment: Vec<Coord<f64>> = edge.geometry.coords().cloned().collect();
+            if !forward {
+                segment.reverse();
+            }
+
+            if coords.last() == segment.first() {
+                coords.extend(segment.into_iter().skip(1));
+            } else {
+                coords.extend(segment);
+            }
+
+            total_length += edge.length;
+        }
+
+        Ok(Self {
+            linestring: LineString::new(coords),
+            total_length,
+        })
+    }
+
+    fn find_edge_between<'a>(
+        network: &'a Network,
+        from: NodeId,
+        to: NodeId,
+    ) -> RoutingResult<(&'a crate::network::Edge, bool)> {
+        network
+            .edges
+            .values()
+            .find_map(|edge| {
+                if edge.source == from && edge.target == to {
+                    Some((edge, true))
+                } else if edge.source == to && edge.target == from {
+                    Some((edge, false))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| {
+                RoutingError::Geometry(format!("No edge found between nodes {} and {}", from, to))
+            })
+    }
+
+    /// Serialize the route as a GeoJSON `Feature` with a `LineString` geometry.
+    pub fn to_geojson(&self) -> Feature {
+        let geometry = Geometry::new(Value::from(&self.linestring));
+        Feature {
+            bbox: None,
+            geometry: Some(geometry),
+            id: None,
+            properties: Some(
+                [("length_m".to_string(), self.total_length.into())]
+                    .into_iter()
+                    .collect(),
+            ),
+            foreign_members: None,
+        }
+    }
+
+    /// Encode the route as a polyline string at the given coordinate precision
+    /// (5 is the common Google/OSRM default).
+    pub fn to_polyline(&self, precision: u32) -> RoutingResult<String> {
+        polyline::encode_coordinates(self.linestring.clone(), precision)
+            .map_err(|e| RoutingError::Geometry(format!("Failed to encode polyline: {}", e)))
+    }
+}
+
+impl IsochroneResult {
+    /// Collect the geometries of every edge touching at least one reachable
+    /// node, suitable for rendering the catchment area as a GeoJSON
+    /// `MultiLineString`.
+    pub fn reachable_edges(&self, network: &Network) -> Vec<LineString<f64>> {
+        network
+            .edges
+            .values()
+            .filter(|edge| {
+                self.travel_costs.contains_key(&edge.source)
+                    || self.travel_costs.contains_key(&edge.target)
+            })
+            .map(|edge| edge.geometry.clone())
+            .collect()
+    }
+
+    /// Serialize `reachable_edges` as a GeoJSON `FeatureCollection` of
+    /// `MultiLineString` geometry (one feature, one multi-line).
+    pub fn to_geojson_multilinestring(&self, network: &Network) -> FeatureCollection {
+        let lines: Vec<Vec<Vec<f64>>> = self
+            .reachable_edges(network)
+            .iter()
+            .map(|line| line.coords().map(|c| vec![c.x, c.y]).collect())
+            .collect();
+
+        let geometry = Geometry::new(Value::MultiLineString(lines));
+        let feature = Feature {
+            bbox: None,
+            geometry: Some(geometry),
+            id: None,
+            properties: Some(
+                [
+                    ("start_node".to_string(), self.start_node.into()),
+                    ("max_cost".to_string(), self.max_cost.into()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            foreign_members: None,
+        };
+
+        FeatureCollection {
+            bbox: None,
+            features: vec![feature],
+            foreign_members: None,
+        }
+    }
+}
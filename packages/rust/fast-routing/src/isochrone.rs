@@ -1,9 +1,91 @@
-use crate::{RoutingResult, ContractionHierarchy, network::{NodeId, Cost, Network}};
-use std::collections::{HashMap, BinaryHeap};
+use crate::{RoutingResult, ContractionHierarchy, network::{NodeId, EdgeId, Cost, Network, RoutingMode}};
+use crate::utils::Utils;
+use std::collections::{HashMap, HashSet, BinaryHeap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::cell::RefCell;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use petgraph::visit::EdgeRef;
 use std::cmp::Ordering;
 use polars::prelude::*;
+use geo::ConvexHull;
+use geo_types::{Point, Polygon, LineString, MultiPoint, Coord, Geometry};
+use arrow::{
+    array::{Float64Array, UInt64Array, BinaryArray},
+    record_batch::RecordBatch,
+    datatypes::{DataType, Field, Schema},
+};
+use parquet::{
+    file::properties::WriterProperties,
+    arrow::ArrowWriter,
+};
+use serde_json;
+
+/// Shared stop flag for `IsochroneCalculator::calculate_batch_streaming`,
+/// checked between sources so a long batch run can be stopped cleanly from
+/// another thread (e.g. a UI cancel button) and still return the results
+/// already computed.
+pub type CancellationToken = Arc<AtomicBool>;
+
+/// How exhaustively `IsochroneCalculator::calculate` explores the frontier.
+///
+/// `Exact` is the original unbounded Dijkstra-style expansion. `Greedy` and
+/// `Beam` trade accuracy for speed on very large networks by discarding all
+/// but the `width` lowest-cost frontier entries after each node is expanded;
+/// `Greedy` is just `Beam { width: 1 }` spelled out for callers who want the
+/// cheapest possible approximate run. Nodes that would only have been
+/// reached through a discarded frontier entry are silently missing from the
+/// result's `travel_costs` -- check `IsochroneResult::exact` /
+/// `frontier_entries_discarded` to tell whether that happened.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SearchMode {
+    Exact,
+    Greedy,
+    Beam { width: usize },
+}
+
+impl SearchMode {
+    /// Frontier cap to enforce, or `None` for unbounded (`Exact`).
+    fn frontier_width(&self) -> Option<usize> {
+        match self {
+            SearchMode::Exact => None,
+            SearchMode::Greedy => Some(1),
+            SearchMode::Beam { width } => Some(*width),
+        }
+    }
+}
+
+/// Alternative search engines for `IsochroneCalculator::calculate_with_mode`.
+///
+/// Distinct from `SearchMode`, which only bounds the frontier width of a
+/// fixed Dijkstra sweep: `IsochroneEngine` picks the sweep itself, so a
+/// caller can compare exact Dijkstra against goal-free geometric pruning or
+/// a fixed-width beam on the same network and quantify the accuracy/speed
+/// tradeoff.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IsochroneEngine {
+    /// Plain unbounded Dijkstra -- the engine `calculate` always uses.
+    Dijkstra,
+    /// Prunes a frontier candidate the moment the straight-line (haversine)
+    /// distance from the start node to it, divided by `mode`'s fastest
+    /// possible speed, already exceeds `max_cost`. That quotient is a lower
+    /// bound on the true travel cost of any path to the candidate (no real
+    /// path is shorter than the straight line, and none is faster than the
+    /// fastest edge for `mode`, taken as the highest `length / cost` over the
+    /// network and never below `RoutingMode::default_speed`), so this can only discard nodes
+    /// Dijkstra would have discarded anyway once it got to them -- it
+    /// changes how many relaxations the search performs, not the reachable
+    /// set. The single-source analogue of `Network::astar_search`'s
+    /// heuristic, which isochrones can't use directly since they have no
+    /// fixed destination for `h(n)` to estimate distance-to-goal against.
+    AStarPruned,
+    /// Keeps only the `width` lowest-cost frontier entries after each
+    /// expansion -- the same trim `SearchMode::Beam { width }` applies --
+    /// exposed here as an engine choice so it can be benchmarked
+    /// side-by-side with the other two through one entry point.
+    BeamLimited(usize),
+}
 
 /// Result of isochrone calculation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +98,43 @@ pub struct IsochroneResult {
     pub reachable_nodes: usize,
     /// Starting node ID
     pub start_node: NodeId,
+    /// Catchment-area polygon, populated by `calculate_with_polygon` /
+    /// `calculate_polygon_bands`
+    pub polygon: Option<Polygon<f64>>,
+    /// Whether this result came from an unbounded (`SearchMode::Exact`) run
+    pub exact: bool,
+    /// Frontier entries dropped to stay within `SearchMode`'s width; always
+    /// 0 for `Exact`
+    pub frontier_entries_discarded: usize,
+}
+
+/// Default "dig-in" edge-length threshold for `IsochroneResult::to_polygon`'s
+/// concave hull, in the same degree units as the node coordinates `concave_hull`
+/// operates on (rather than meters); chosen conservatively for city-scale
+/// walking/cycling isochrones (roughly 1km at mid-latitudes).
+const DEFAULT_CONCAVE_HULL_ALPHA: f64 = 0.01;
+
+impl IsochroneResult {
+    /// Build a concave-hull catchment polygon directly from this result's
+    /// already-computed `travel_costs`, using only `network` for node
+    /// coordinates.
+    ///
+    /// Unlike `IsochroneCalculator::calculate_with_polygon`, this has no
+    /// `ContractionHierarchy`/mode to interpolate cutoff points along
+    /// partially-traversed edges, so the hull is built from reachable node
+    /// locations alone. `max_cost` lets a caller holding a multi-band result
+    /// build a stricter polygon than `self.max_cost` without recomputing the
+    /// isochrone.
+    pub fn to_polygon(&self, network: &Network, max_cost: Cost) -> Polygon<f64> {
+        let points: Vec<Point<f64>> = self.travel_costs
+            .iter()
+            .filter(|&(_, &cost)| cost <= max_cost)
+            .filter_map(|(&node_id, _)| network.get_node(node_id))
+            .map(|node| node.location)
+            .collect();
+
+        IsochroneCalculator::concave_hull(&points, DEFAULT_CONCAVE_HULL_ALPHA)
+    }
 }
 
 /// State for Dijkstra-based isochrone calculation
@@ -39,97 +158,425 @@ impl PartialOrd for IsochroneState {
     }
 }
 
+thread_local! {
+    /// Per-thread distance map and priority queue for `dijkstra_isochrone_pruned`,
+    /// reused across calls on the same thread instead of being freshly
+    /// allocated every time. `calculate_batch` runs one isochrone per
+    /// `start_node`/threshold pair on a rayon worker thread, so under a
+    /// dedicated pool this buffer's backing allocation is amortized over the
+    /// whole batch assigned to that worker rather than paid once per call.
+    static ISOCHRONE_SCRATCH: RefCell<(HashMap<NodeId, Cost>, BinaryHeap<IsochroneState>)> =
+        RefCell::new((HashMap::new(), BinaryHeap::new()));
+}
+
+/// Result of `IsochroneCalculator::calculate_transit_isochrone`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitIsochroneResult {
+    /// Expected cost (ride time plus expected wait for a boarded line) of
+    /// reaching `start_node` by transit, keyed by origin node. Unlike
+    /// `IsochroneResult::travel_costs`, this is a cost *to* `start_node`, not
+    /// *from* it -- see `calculate_transit_isochrone`.
+    pub expected_cost: HashMap<NodeId, Cost>,
+    /// Each node's attractive-line set: the outgoing transit edges a
+    /// traveller there should be willing to board, in the order they were
+    /// added while greedily growing the set.
+    pub attractive_lines: HashMap<NodeId, Vec<EdgeId>>,
+    /// Starting (destination) node ID
+    pub start_node: NodeId,
+    /// Maximum cost used in calculation
+    pub max_cost: Cost,
+    /// Number of reachable nodes
+    pub reachable_nodes: usize,
+}
+
+/// A candidate transit line `origin --edge_id--> target` being considered
+/// for `target`'s already-known label, used while building the hyperpath in
+/// `calculate_transit_isochrone`. Ordered so a `BinaryHeap` pops the lowest
+/// `value` first, like `IsochroneState`.
+#[derive(Debug, Clone, PartialEq)]
+struct HyperpathCandidate {
+    /// Ride cost of the edge plus the already-settled label of its target.
+    value: Cost,
+    origin: NodeId,
+    edge_id: EdgeId,
+    frequency: f64,
+}
+
+impl Eq for HyperpathCandidate {}
+
+impl Ord for HyperpathCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.value.partial_cmp(&self.value).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for HyperpathCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Enhanced isochrone calculator for real catchment areas
 pub struct IsochroneCalculator;
 
 impl IsochroneCalculator {
-    /// Calculate detailed isochrone from a starting node
+    /// Calculate detailed isochrone from a starting node, resolving edge
+    /// costs for `mode` and honoring `Edge::is_accessible(mode)`.
+    ///
+    /// `search_mode` trades accuracy for speed; pass `SearchMode::Exact` for
+    /// the original unbounded behavior.
     pub fn calculate(
         ch: &ContractionHierarchy,
         start_node_id: NodeId,
         max_cost: Cost,
+        mode: RoutingMode,
+        search_mode: SearchMode,
     ) -> RoutingResult<IsochroneResult> {
         log::info!("Calculating isochrone from node {} with max cost {:.0}s ({:.1} min)", start_node_id, max_cost, max_cost/60.0);
-        
+
+        if !ch.original_network.is_in_main_component(start_node_id, mode) {
+            return Err(crate::RoutingError::Isochrone(format!(
+                "Start node {} is isolated from the network's main component for mode {:?}; isochrone would be misleadingly small",
+                start_node_id, mode
+            )));
+        }
+
         // Perform Dijkstra search to find all reachable nodes within cost limit
-        let travel_costs = Self::dijkstra_isochrone(ch, start_node_id, max_cost)?;
-        
+        let (travel_costs, frontier_entries_discarded) = Self::dijkstra_isochrone(ch, start_node_id, max_cost, mode, search_mode)?;
+
         let reachable_nodes = travel_costs.len();
         log::info!("Found {} reachable nodes within {:.0}s ({:.1} min) travel time", reachable_nodes, max_cost, max_cost/60.0);
-        
+
         Ok(IsochroneResult {
             travel_costs,
             max_cost,
             reachable_nodes,
             start_node: start_node_id,
+            polygon: None,
+            exact: search_mode == SearchMode::Exact,
+            frontier_entries_discarded,
         })
     }
-    
-    /// Dijkstra-based search to find all reachable nodes within cost limit
+
+    /// Calculate an isochrone with an explicit `IsochroneEngine` instead of
+    /// `calculate`'s fixed Dijkstra sweep, so `Dijkstra`/`AStarPruned`/
+    /// `BeamLimited` results for the same start node and cost budget can be
+    /// compared directly.
+    pub fn calculate_with_mode(
+        ch: &ContractionHierarchy,
+        start_node_id: NodeId,
+        max_cost: Cost,
+        mode: RoutingMode,
+        engine: IsochroneEngine,
+    ) -> RoutingResult<IsochroneResult> {
+        log::info!("Calculating isochrone (engine {:?}) from node {} with max cost {:.0}s ({:.1} min)", engine, start_node_id, max_cost, max_cost/60.0);
+
+        if !ch.original_network.is_in_main_component(start_node_id, mode) {
+            return Err(crate::RoutingError::Isochrone(format!(
+                "Start node {} is isolated from the network's main component for mode {:?}; isochrone would be misleadingly small",
+                start_node_id, mode
+            )));
+        }
+
+        let search_mode = match engine {
+            IsochroneEngine::Dijkstra | IsochroneEngine::AStarPruned => SearchMode::Exact,
+            IsochroneEngine::BeamLimited(width) => SearchMode::Beam { width },
+        };
+
+        let geometric_prune = if engine == IsochroneEngine::AStarPruned {
+            let start_location = ch.original_network.get_node(start_node_id)
+                .map(|node| node.location)
+                .ok_or_else(|| crate::RoutingError::Network(format!("Start node {} not found", start_node_id)))?;
+            let max_speed_mps = ch.original_network.edges.values()
+                .filter(|edge| edge.is_accessible(mode))
+                .filter_map(|edge| edge.get_cost(mode).filter(|&cost| cost > 0.0).map(|cost| edge.length / cost))
+                .fold(mode.default_speed() * 1000.0 / 3600.0, f64::max);
+            Some((start_location, max_speed_mps))
+        } else {
+            None
+        };
+
+        let (travel_costs, frontier_entries_discarded) = Self::dijkstra_isochrone_pruned(
+            ch, start_node_id, max_cost, mode, search_mode, geometric_prune,
+        )?;
+
+        let reachable_nodes = travel_costs.len();
+        log::info!("Found {} reachable nodes within {:.0}s ({:.1} min) travel time", reachable_nodes, max_cost, max_cost/60.0);
+
+        Ok(IsochroneResult {
+            travel_costs,
+            max_cost,
+            reachable_nodes,
+            start_node: start_node_id,
+            polygon: None,
+            // AStarPruned discards the same nodes Dijkstra would have, so it's
+            // exact too; only BeamLimited trades reachable-set completeness
+            // for speed.
+            exact: !matches!(engine, IsochroneEngine::BeamLimited(_)),
+            frontier_entries_discarded,
+        })
+    }
+
+    /// Dijkstra-based search to find all reachable nodes within cost limit.
+    ///
+    /// When `search_mode` bounds the frontier, the priority queue is
+    /// trimmed to its `width` lowest-cost entries after each node is
+    /// expanded, discarding the rest; the returned count is how many
+    /// entries were dropped this way over the whole search.
     fn dijkstra_isochrone(
         ch: &ContractionHierarchy,
         start_node_id: NodeId,
         max_cost: Cost,
-    ) -> RoutingResult<HashMap<NodeId, Cost>> {
+        mode: RoutingMode,
+        search_mode: SearchMode,
+    ) -> RoutingResult<(HashMap<NodeId, Cost>, usize)> {
+        Self::dijkstra_isochrone_pruned(ch, start_node_id, max_cost, mode, search_mode, None)
+    }
+
+    /// Same sweep as `dijkstra_isochrone`, plus an optional geometric lower
+    /// bound: `geometric_prune` is `(start_location, max_speed_mps)` for
+    /// `IsochroneEngine::AStarPruned`, used to reject a candidate neighbor
+    /// before it's even pushed to the heap -- see `IsochroneEngine::AStarPruned`
+    /// for why that bound is admissible.
+    fn dijkstra_isochrone_pruned(
+        ch: &ContractionHierarchy,
+        start_node_id: NodeId,
+        max_cost: Cost,
+        mode: RoutingMode,
+        search_mode: SearchMode,
+        geometric_prune: Option<(Point<f64>, f64)>,
+    ) -> RoutingResult<(HashMap<NodeId, Cost>, usize)> {
         let _start_idx = ch.original_network.get_node_index(start_node_id)
             .ok_or_else(|| crate::RoutingError::Network(format!("Start node {} not found", start_node_id)))?;
-        
-        let mut distances = HashMap::new();
-        let mut heap = BinaryHeap::new();
-        
-        // Initialize with start node
-        distances.insert(start_node_id, 0.0);
-        heap.push(IsochroneState { node: start_node_id, cost: 0.0 });
-        
-        while let Some(IsochroneState { node: current_node_id, cost: current_cost }) = heap.pop() {
-            // Skip if we've already processed this node with a better cost
-            if let Some(&best_cost) = distances.get(&current_node_id) {
-                if current_cost > best_cost {
+
+        ISOCHRONE_SCRATCH.with(|scratch| {
+            let mut scratch = scratch.borrow_mut();
+            let (distances, heap) = &mut *scratch;
+            distances.clear();
+            heap.clear();
+
+            let frontier_width = search_mode.frontier_width();
+            let mut frontier_entries_discarded = 0usize;
+
+            // Initialize with start node
+            distances.insert(start_node_id, 0.0);
+            heap.push(IsochroneState { node: start_node_id, cost: 0.0 });
+
+            while let Some(IsochroneState { node: current_node_id, cost: current_cost }) = heap.pop() {
+                // Skip if we've already processed this node with a better cost
+                if let Some(&best_cost) = distances.get(&current_node_id) {
+                    if current_cost > best_cost {
+                        continue;
+                    }
+                }
+
+                // Skip if cost exceeds limit
+                if current_cost > max_cost {
                     continue;
                 }
+
+                // Find the node index for this node ID
+                if let Some(current_idx) = ch.original_network.get_node_index(current_node_id) {
+                    // Explore neighbors
+                    for edge in ch.original_network.graph.edges(current_idx) {
+                        let neighbor_idx = edge.target();
+                        let Some(edge_cost) = ch.original_network.edge_cost(edge.weight().edge_id, mode) else { continue };
+                        let new_cost = current_cost + edge_cost;
+
+                        // Skip if exceeds max cost
+                        if new_cost > max_cost {
+                            continue;
+                        }
+
+                        // Get neighbor node data
+                        if let Some(neighbor_node) = ch.original_network.graph.node_weight(neighbor_idx) {
+                            let neighbor_id = neighbor_node.id;
+
+                            if let Some((start_location, max_speed_mps)) = geometric_prune {
+                                let lower_bound = Utils::haversine_distance(&start_location, &neighbor_node.location) / max_speed_mps;
+                                if lower_bound > max_cost {
+                                    continue;
+                                }
+                            }
+
+                            // Check if this is a better path to the neighbor
+                            let is_better = match distances.get(&neighbor_id) {
+                                Some(&existing_cost) => new_cost < existing_cost,
+                                None => true,
+                            };
+
+                            if is_better {
+                                distances.insert(neighbor_id, new_cost);
+                                heap.push(IsochroneState { node: neighbor_id, cost: new_cost });
+                            }
+                        }
+                    }
+                }
+
+                if let Some(width) = frontier_width {
+                    if heap.len() > width {
+                        let mut frontier: Vec<IsochroneState> = heap.drain().collect();
+                        frontier.sort_by(|a, b| a.cost.partial_cmp(&b.cost).unwrap_or(Ordering::Equal));
+                        frontier_entries_discarded += frontier.len() - width;
+                        heap.extend(frontier.into_iter().take(width));
+                    }
+                }
             }
-            
-            // Skip if cost exceeds limit
-            if current_cost > max_cost {
+
+            log::debug!("Dijkstra isochrone found {} reachable nodes ({} frontier entries discarded)", distances.len(), frontier_entries_discarded);
+            // Scratch buffers stay in thread-local storage for the next call
+            // on this worker; only the result itself is cloned out.
+            Ok((distances.clone(), frontier_entries_discarded))
+        })
+    }
+
+    /// Transit isochrone around `start_node`, computed with the Spiess &
+    /// Florian optimal-strategy hyperpath rule instead of plain Dijkstra:
+    /// at each node, lines are added to an attractive boarding set only
+    /// while doing so strictly lowers the combined expected cost (the
+    /// frequency-weighted average of the lines' downstream costs plus the
+    /// expected wait `1 / sum(frequencies)` for the first of them to
+    /// arrive), rather than committing to the single fastest line.
+    ///
+    /// The label-setting pass runs backward from `start_node` acting as the
+    /// fixed destination -- the direction the optimal-strategy algorithm is
+    /// defined for -- so `expected_cost[n]` is the cost of reaching
+    /// `start_node` by transit from `n`, not the other way around. Nodes
+    /// are finalized the moment their attractive-line set stops improving
+    /// (a candidate is rejected, or the queue reaches their label so no
+    /// cheaper candidate remains) or every one of their transit edges has
+    /// been considered, mirroring Dijkstra's settling order.
+    ///
+    /// Only edges with `Edge::frequency` set (`is_accessible(Transit)`) are
+    /// considered; ride cost is derived from `edge.length` at
+    /// `RoutingMode::Transit.default_speed()` rather than `Network::edge_cost`,
+    /// since ordinary edges never carry a baked-in `Transit` cost entry.
+    pub fn calculate_transit_isochrone(
+        ch: &ContractionHierarchy,
+        start_node: NodeId,
+        max_cost: Cost,
+    ) -> RoutingResult<TransitIsochroneResult> {
+        let network = &ch.original_network;
+        network.get_node_index(start_node)
+            .ok_or_else(|| crate::RoutingError::Isochrone(format!("Start node {} not found", start_node)))?;
+
+        log::info!("Calculating transit isochrone to node {} with max cost {:.0}s ({:.1} min)", start_node, max_cost, max_cost / 60.0);
+
+        let mut label: HashMap<NodeId, Cost> = HashMap::new();
+        let mut freq_sum: HashMap<NodeId, f64> = HashMap::new();
+        let mut weighted_sum: HashMap<NodeId, f64> = HashMap::new();
+        let mut attractive_lines: HashMap<NodeId, Vec<EdgeId>> = HashMap::new();
+        let mut remaining: HashMap<NodeId, usize> = HashMap::new();
+        let mut closed: HashSet<NodeId> = HashSet::new();
+        let mut heap: BinaryHeap<HyperpathCandidate> = BinaryHeap::new();
+
+        label.insert(start_node, 0.0);
+        closed.insert(start_node);
+        Self::relax_transit_predecessors(network, start_node, 0.0, &closed, &mut heap);
+
+        while let Some(HyperpathCandidate { value, origin, edge_id, frequency }) = heap.pop() {
+            if closed.contains(&origin) {
                 continue;
             }
-            
-            // Find the node index for this node ID
-            if let Some(current_idx) = ch.original_network.get_node_index(current_node_id) {
-                // Explore neighbors
-                for edge in ch.original_network.graph.edges(current_idx) {
-                    let neighbor_idx = edge.target();
-                    let edge_cost = edge.weight().cost;
-                    let new_cost = current_cost + edge_cost;
-                    
-                    // Skip if exceeds max cost
-                    if new_cost > max_cost {
-                        continue;
-                    }
-                    
-                    // Get neighbor node data
-                    if let Some(neighbor_node) = ch.original_network.graph.node_weight(neighbor_idx) {
-                        let neighbor_id = neighbor_node.id;
-                        
-                        // Check if this is a better path to the neighbor
-                        let is_better = match distances.get(&neighbor_id) {
-                            Some(&existing_cost) => new_cost < existing_cost,
-                            None => true,
-                        };
-                        
-                        if is_better {
-                            distances.insert(neighbor_id, new_cost);
-                            heap.push(IsochroneState { node: neighbor_id, cost: new_cost });
-                        }
-                    }
+            if value > max_cost {
+                break;
+            }
+
+            // A zero-frequency entry at exactly `origin`'s label is the settle
+            // marker pushed below: every candidate still queued costs at least
+            // that much, so none of them can lower the label any further.
+            if frequency == 0.0 && label.get(&origin) == Some(&value) {
+                closed.insert(origin);
+                Self::relax_transit_predecessors(network, origin, value, &closed, &mut heap);
+                continue;
+            }
+
+            let remaining_count = remaining.entry(origin).or_insert_with(|| Self::transit_out_degree(network, origin));
+            *remaining_count = remaining_count.saturating_sub(1);
+
+            let fs = *freq_sum.get(&origin).unwrap_or(&0.0);
+            let ws = *weighted_sum.get(&origin).unwrap_or(&0.0);
+
+            let (accepted, new_label) = if fs == 0.0 {
+                (true, (1.0 + frequency * value) / frequency)
+            } else {
+                let candidate_label = (1.0 + ws + frequency * value) / (fs + frequency);
+                (candidate_label < label[&origin], candidate_label)
+            };
+
+            let finalize = if accepted {
+                freq_sum.insert(origin, fs + frequency);
+                weighted_sum.insert(origin, ws + frequency * value);
+                label.insert(origin, new_label);
+                attractive_lines.entry(origin).or_default().push(edge_id);
+                if remaining[&origin] != 0 {
+                    heap.push(HyperpathCandidate { value: new_label, origin, edge_id, frequency: 0.0 });
                 }
+                remaining[&origin] == 0
+            } else {
+                true
+            };
+
+            if finalize {
+                closed.insert(origin);
+                let final_label = label[&origin];
+                Self::relax_transit_predecessors(network, origin, final_label, &closed, &mut heap);
             }
         }
-        
-        log::debug!("Dijkstra isochrone found {} reachable nodes", distances.len());
-        Ok(distances)
+
+        let reachable_nodes = label.len();
+        log::info!("Transit isochrone to node {} found {} reachable nodes within {:.0}s ({:.1} min)", start_node, reachable_nodes, max_cost, max_cost / 60.0);
+
+        Ok(TransitIsochroneResult {
+            expected_cost: label,
+            attractive_lines,
+            start_node,
+            max_cost,
+            reachable_nodes,
+        })
     }
-    
+
+    /// Number of transit-accessible edges incident to `node_id`, used to
+    /// detect when every one of a node's lines has been considered even if
+    /// none of them were ever rejected.
+    fn transit_out_degree(network: &Network, node_id: NodeId) -> usize {
+        let Some(idx) = network.get_node_index(node_id) else { return 0 };
+        network.graph.edges(idx)
+            .filter(|edge_ref| {
+                network.edges.get(&edge_ref.weight().edge_id)
+                    .map_or(false, |edge| edge.is_accessible(RoutingMode::Transit))
+            })
+            .count()
+    }
+
+    /// Push a candidate arc for every not-yet-closed transit neighbor of the
+    /// just-finalized `node_id`, using its settled `node_label`.
+    fn relax_transit_predecessors(
+        network: &Network,
+        node_id: NodeId,
+        node_label: Cost,
+        closed: &HashSet<NodeId>,
+        heap: &mut BinaryHeap<HyperpathCandidate>,
+    ) {
+        let Some(idx) = network.get_node_index(node_id) else { return };
+        for edge_ref in network.graph.edges(idx) {
+            let Some(edge) = network.edges.get(&edge_ref.weight().edge_id) else { continue };
+            let Some(frequency) = edge.frequency.filter(|_| edge.is_accessible(RoutingMode::Transit)) else { continue };
+            let Some(origin_node) = network.graph.node_weight(edge_ref.target()) else { continue };
+            let origin = origin_node.id;
+            if closed.contains(&origin) {
+                continue;
+            }
+            let ride_cost = (edge.length / 1000.0) / RoutingMode::Transit.default_speed() * 3600.0;
+            heap.push(HyperpathCandidate {
+                value: ride_cost + node_label,
+                origin,
+                edge_id: edge.id,
+                frequency,
+            });
+        }
+    }
+
     /// Export isochrone results as geoparquet with node coordinates
     pub fn export_as_geoparquet(
         results: &[(NodeId, Vec<IsochroneResult>)],
@@ -187,19 +634,487 @@ impl IsochroneCalculator {
         Ok(())
     }
     
+    /// Build the GeoParquet `geo` file-level metadata (version 1.0.0, WKB
+    /// encoding) for a single-geometry-column export named `geometry`.
+    fn geoparquet_file_metadata(geometry_type: &str, bbox: [f64; 4]) -> HashMap<String, String> {
+        let geo = serde_json::json!({
+            "version": "1.0.0",
+            "primary_column": "geometry",
+            "columns": {
+                "geometry": {
+                    "encoding": "WKB",
+                    "geometry_types": [geometry_type],
+                    "bbox": bbox,
+                }
+            }
+        });
+
+        [("geo".to_string(), geo.to_string())].into_iter().collect()
+    }
+
+    /// Export catchment-area polygons (not just reachable-node points) as
+    /// GeoParquet: one row per result, with the `IsochroneResult::polygon`
+    /// encoded as WKB in a `geometry` column. Results with no polygon (i.e.
+    /// not produced via `calculate_with_polygon`/`calculate_polygon_bands`,
+    /// or via `IsochroneResult::to_polygon`) are skipped with a warning.
+    pub fn export_polygons_as_geoparquet(
+        results: &[(NodeId, IsochroneResult)],
+        output_path: &str,
+    ) -> RoutingResult<()> {
+        log::info!("Exporting {} isochrone polygons as geoparquet to {}", results.len(), output_path);
+
+        let mut start_nodes = Vec::new();
+        let mut max_costs = Vec::new();
+        let mut reachable_counts = Vec::new();
+        let mut geometries: Vec<Vec<u8>> = Vec::new();
+        let mut bbox = [f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY];
+
+        for (start_node, result) in results {
+            let Some(polygon) = &result.polygon else {
+                log::warn!("Skipping isochrone for node {} with no polygon computed", start_node);
+                continue;
+            };
+
+            for coord in polygon.exterior().coords() {
+                bbox[0] = bbox[0].min(coord.x);
+                bbox[1] = bbox[1].min(coord.y);
+                bbox[2] = bbox[2].max(coord.x);
+                bbox[3] = bbox[3].max(coord.y);
+            }
+
+            let wkb_bytes = wkb::geom_to_wkb(&Geometry::Polygon(polygon.clone())).map_err(|e| {
+                crate::RoutingError::Isochrone(format!("Failed to encode isochrone polygon as WKB: {:?}", e))
+            })?;
+
+            start_nodes.push(*start_node as u64);
+            max_costs.push(result.max_cost);
+            reachable_counts.push(result.reachable_nodes as u64);
+            geometries.push(wkb_bytes);
+        }
+
+        let schema = Schema::new_with_metadata(
+            vec![
+                Field::new("start_node", DataType::UInt64, false),
+                Field::new("max_cost_seconds", DataType::Float64, false),
+                Field::new("reachable_nodes", DataType::UInt64, false),
+                Field::new("geometry", DataType::Binary, false),
+            ],
+            Self::geoparquet_file_metadata("Polygon", bbox),
+        );
+
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(UInt64Array::from(start_nodes)),
+                Arc::new(Float64Array::from(max_costs)),
+                Arc::new(UInt64Array::from(reachable_counts)),
+                Arc::new(BinaryArray::from_iter_values(geometries.iter().map(|g| g.as_slice()))),
+            ],
+        ).map_err(|e| crate::RoutingError::Isochrone(format!("Failed to build record batch: {}", e)))?;
+
+        let file = std::fs::File::create(output_path)
+            .map_err(|e| crate::RoutingError::Network(format!("Failed to create output file: {}", e)))?;
+        let props = WriterProperties::builder().build();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))
+            .map_err(|e| crate::RoutingError::Isochrone(format!("Failed to create Parquet writer: {}", e)))?;
+
+        writer.write(&batch)
+            .map_err(|e| crate::RoutingError::Isochrone(format!("Failed to write record batch: {}", e)))?;
+        writer.close()
+            .map_err(|e| crate::RoutingError::Isochrone(format!("Failed to close Parquet writer: {}", e)))?;
+
+        log::info!("Successfully exported {} isochrone polygons to {}", geometries.len(), output_path);
+        Ok(())
+    }
+
     /// Calculate multiple isochrones for different cost thresholds
     pub fn calculate_multiple(
         ch: &ContractionHierarchy,
         start_node_id: NodeId,
         cost_thresholds: &[Cost],
+        mode: RoutingMode,
+        search_mode: SearchMode,
     ) -> RoutingResult<Vec<IsochroneResult>> {
         let mut results = Vec::new();
-        
+
         for &max_cost in cost_thresholds {
-            let result = Self::calculate(ch, start_node_id, max_cost)?;
+            let result = Self::calculate(ch, start_node_id, max_cost, mode, search_mode)?;
             results.push(result);
         }
-        
+
+        Ok(results)
+    }
+
+    /// Calculate isochrones for every `start_node` in `start_nodes` against
+    /// every threshold in `cost_thresholds`, fanned out across a dedicated
+    /// rayon thread pool sized to `num_threads` (or
+    /// `std::thread::available_parallelism` if `None`), rather than rayon's
+    /// implicit global pool -- borrowing the `ThreadPoolBuilder` + `par_iter`
+    /// pattern ED_LRR's router uses for its per-source CH queries.
+    ///
+    /// `calculate` only borrows `ch` and performs no interior mutation, so
+    /// `ContractionHierarchy` is `Sync` and safe to share across the pool's
+    /// worker threads without cloning. Each worker reuses its own
+    /// `ISOCHRONE_SCRATCH` distance map and priority queue across every
+    /// isochrone it computes (see `dijkstra_isochrone_pruned`), so throughput
+    /// scales with cores instead of being dominated by repeated allocation.
+    /// If `progress` is given, it is incremented once per completed start
+    /// node (not per threshold), so a caller can drive a progress/ETA
+    /// printout off it without needing any channel back from the worker
+    /// threads.
+    pub fn calculate_batch(
+        ch: &ContractionHierarchy,
+        start_nodes: &[NodeId],
+        cost_thresholds: &[Cost],
+        mode: RoutingMode,
+        search_mode: SearchMode,
+        num_threads: Option<usize>,
+        progress: Option<&AtomicUsize>,
+    ) -> RoutingResult<Vec<(NodeId, Vec<RoutingResult<IsochroneResult>>)>> {
+        let num_threads = num_threads.unwrap_or_else(|| {
+            std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+        });
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| crate::RoutingError::Isochrone(format!("Failed to build thread pool: {}", e)))?;
+
+        Ok(pool.install(|| {
+            start_nodes
+                .par_iter()
+                .map(|&start_node_id| {
+                    let results = cost_thresholds
+                        .iter()
+                        .map(|&max_cost| Self::calculate(ch, start_node_id, max_cost, mode, search_mode))
+                        .collect();
+                    if let Some(counter) = progress {
+                        counter.fetch_add(1, AtomicOrdering::Relaxed);
+                    }
+                    (start_node_id, results)
+                })
+                .collect()
+        }))
+    }
+
+    /// Same batch fan-out as `calculate_batch`, but reports a
+    /// `(index, start_node, outcome)` event on `progress_tx` as each
+    /// individual isochrone finishes (`index` is `start_node`'s position in
+    /// `start_nodes`), and checks `cancel` before starting each new source so
+    /// a caller can stop a long run early from another thread -- like ED_LRR's
+    /// `SearchState` status updates, but pushed per completion instead of on
+    /// a fixed timer. `RoutingError` isn't `Clone` (it wraps
+    /// `std::io::Error`/`serde_json::Error`), so a failed calculation is
+    /// carried on the channel as its rendered message rather than the error
+    /// type itself.
+    ///
+    /// Already-running sources finish normally once `cancel` is set; the
+    /// returned vec holds only the sources that completed, so a caller can
+    /// still flush a partial result through `export_as_geoparquet`.
+    pub fn calculate_batch_streaming(
+        ch: &ContractionHierarchy,
+        start_nodes: &[NodeId],
+        cost_thresholds: &[Cost],
+        mode: RoutingMode,
+        search_mode: SearchMode,
+        num_threads: Option<usize>,
+        progress_tx: Option<crossbeam_channel::Sender<(usize, NodeId, Result<IsochroneResult, String>)>>,
+        cancel: Option<&CancellationToken>,
+    ) -> RoutingResult<Vec<(NodeId, Vec<RoutingResult<IsochroneResult>>)>> {
+        let num_threads = num_threads.unwrap_or_else(|| {
+            std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+        });
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| crate::RoutingError::Isochrone(format!("Failed to build thread pool: {}", e)))?;
+
+        Ok(pool.install(|| {
+            start_nodes
+                .par_iter()
+                .enumerate()
+                .filter_map(|(index, &start_node_id)| {
+                    // Checked once per source, not per threshold, so a
+                    // source already being worked on still finishes cleanly.
+                    if cancel.map(|token| token.load(AtomicOrdering::Relaxed)).unwrap_or(false) {
+                        return None;
+                    }
+
+                    let results: Vec<RoutingResult<IsochroneResult>> = cost_thresholds
+                        .iter()
+                        .map(|&max_cost| {
+                            let result = Self::calculate(ch, start_node_id, max_cost, mode, search_mode);
+                            if let Some(tx) = &progress_tx {
+                                let event = (index, start_node_id, result.as_ref().map(|r| r.clone()).map_err(|e| e.to_string()));
+                                let _ = tx.send(event);
+                            }
+                            result
+                        })
+                        .collect();
+
+                    Some((start_node_id, results))
+                })
+                .collect()
+        }))
+    }
+
+    /// Calculate an isochrone starting from the network node nearest to
+    /// `(lon, lat)`, snapping via `Network::nearest_node`'s R-tree lookup.
+    ///
+    /// Returns the isochrone alongside the Haversine snap distance in
+    /// meters, so callers can reject points implausibly far from the
+    /// network (e.g. a bad geocode) instead of silently routing from
+    /// whatever node happened to be nearest.
+    pub fn calculate_from_coords(
+        ch: &ContractionHierarchy,
+        lon: f64,
+        lat: f64,
+        max_cost: Cost,
+        mode: RoutingMode,
+        search_mode: SearchMode,
+    ) -> RoutingResult<(IsochroneResult, f64)> {
+        let point = Point::new(lon, lat);
+        let network = &ch.original_network;
+        let start_node = network.nearest_node(&point).ok_or_else(|| {
+            crate::RoutingError::Isochrone("Network has no nodes to snap to".to_string())
+        })?;
+        let snap_distance = network.get_node(start_node)
+            .map(|node| Utils::haversine_distance(&point, &node.location))
+            .unwrap_or(f64::INFINITY);
+
+        let result = Self::calculate(ch, start_node, max_cost, mode, search_mode)?;
+        Ok((result, snap_distance))
+    }
+
+    /// Calculate an isochrone and attach a concave-hull catchment polygon
+    /// built from the reachable node coordinates plus interpolated cutoff
+    /// points along partially-traversed edges.
+    pub fn calculate_with_polygon(
+        ch: &ContractionHierarchy,
+        network: &Network,
+        start_node_id: NodeId,
+        max_cost: Cost,
+        mode: RoutingMode,
+        alpha: f64,
+        search_mode: SearchMode,
+    ) -> RoutingResult<IsochroneResult> {
+        let mut result = Self::calculate(ch, start_node_id, max_cost, mode, search_mode)?;
+        let points = Self::catchment_points(ch, network, &result.travel_costs, max_cost, mode);
+        result.polygon = Some(Self::concave_hull(&points, alpha));
+        Ok(result)
+    }
+
+    /// Calculate several nested polygons for increasing cost thresholds in a
+    /// single Dijkstra pass, one `IsochroneResult` per threshold.
+    ///
+    /// Always runs `SearchMode::Exact`: bounding the frontier would distort
+    /// the nested bands inconsistently across thresholds, which defeats the
+    /// point of sharing one pass between them.
+    pub fn calculate_polygon_bands(
+        ch: &ContractionHierarchy,
+        network: &Network,
+        start_node_id: NodeId,
+        cost_thresholds: &[Cost],
+        mode: RoutingMode,
+        alpha: f64,
+    ) -> RoutingResult<Vec<IsochroneResult>> {
+        let max_threshold = cost_thresholds.iter().cloned().fold(0.0, f64::max);
+        let (all_costs, _) = Self::dijkstra_isochrone(ch, start_node_id, max_threshold, mode, SearchMode::Exact)?;
+
+        let mut results = Vec::with_capacity(cost_thresholds.len());
+        for &max_cost in cost_thresholds {
+            let travel_costs: HashMap<NodeId, Cost> = all_costs
+                .iter()
+                .filter(|(_, &cost)| cost <= max_cost)
+                .map(|(&id, &cost)| (id, cost))
+                .collect();
+
+            let points = Self::catchment_points(ch, network, &travel_costs, max_cost, mode);
+            let polygon = Self::concave_hull(&points, alpha);
+
+            results.push(IsochroneResult {
+                reachable_nodes: travel_costs.len(),
+                travel_costs,
+                max_cost,
+                start_node: start_node_id,
+                polygon: Some(polygon),
+                exact: true,
+                frontier_entries_discarded: 0,
+            });
+        }
+
         Ok(results)
     }
+
+    /// Collect reachable node coordinates plus a cutoff point along each edge
+    /// that leads out of the reachable set, placed at the fraction
+    /// `remaining_cost / edge_cost` along the edge geometry.
+    fn catchment_points(
+        ch: &ContractionHierarchy,
+        network: &Network,
+        travel_costs: &HashMap<NodeId, Cost>,
+        max_cost: Cost,
+        mode: RoutingMode,
+    ) -> Vec<Point<f64>> {
+        let mut points: Vec<Point<f64>> = travel_costs
+            .keys()
+            .filter_map(|id| network.get_node(*id))
+            .map(|node| node.location)
+            .collect();
+
+        for (&node_id, &cost_at_node) in travel_costs.iter() {
+            let Some(node_idx) = ch.original_network.get_node_index(node_id) else { continue };
+
+            for edge in ch.original_network.graph.edges(node_idx) {
+                let Some(edge_cost) = ch.original_network.edge_cost(edge.weight().edge_id, mode) else { continue };
+                let new_cost = cost_at_node + edge_cost;
+                if new_cost <= max_cost || edge_cost <= 0.0 {
+                    continue;
+                }
+
+                let Some(neighbor) = ch.original_network.graph.node_weight(edge.target()) else { continue };
+                if travel_costs.contains_key(&neighbor.id) {
+                    continue;
+                }
+
+                let Some(network_edge) = network
+                    .edges
+                    .values()
+                    .find(|e| (e.source == node_id && e.target == neighbor.id)
+                        || (e.source == neighbor.id && e.target == node_id))
+                else { continue };
+
+                let remaining = max_cost - cost_at_node;
+                let fraction = (remaining / edge_cost).clamp(0.0, 1.0);
+                if let Some(point) = Self::point_along_linestring(&network_edge.geometry, fraction) {
+                    points.push(point);
+                }
+            }
+        }
+
+        points
+    }
+
+    /// Interpolate a point at `fraction` (0.0..=1.0) of the way along `line`.
+    fn point_along_linestring(line: &LineString<f64>, fraction: f64) -> Option<Point<f64>> {
+        let coords: Vec<Coord<f64>> = line.coords().cloned().collect();
+        if coords.len() < 2 {
+            return coords.first().map(|c| Point::new(c.x, c.y));
+        }
+
+        let segment_lengths: Vec<f64> = coords
+            .windows(2)
+            .map(|w| ((w[1].x - w[0].x).powi(2) + (w[1].y - w[0].y).powi(2)).sqrt())
+            .collect();
+        let total_length: f64 = segment_lengths.iter().sum();
+        if total_length <= 0.0 {
+            return Some(Point::new(coords[0].x, coords[0].y));
+        }
+
+        let target = fraction * total_length;
+        let mut accumulated = 0.0;
+
+        for (i, &seg_len) in segment_lengths.iter().enumerate() {
+            if accumulated + seg_len >= target || i == segment_lengths.len() - 1 {
+                let t = if seg_len > 0.0 { (target - accumulated) / seg_len } else { 0.0 };
+                let t = t.clamp(0.0, 1.0);
+                let x = coords[i].x + t * (coords[i + 1].x - coords[i].x);
+                let y = coords[i].y + t * (coords[i + 1].y - coords[i].y);
+                return Some(Point::new(x, y));
+            }
+            accumulated += seg_len;
+        }
+
+        coords.last().map(|c| Point::new(c.x, c.y))
+    }
+
+    /// Build a concave hull (chi-shape) from a point cloud: start from the
+    /// convex hull, then repeatedly "dig in" the longest boundary edge by
+    /// replacing it with the two edges to the nearest interior point whenever
+    /// that edge's length exceeds `alpha` and the two replacement edges
+    /// together are shorter than twice its length. That length check stands
+    /// in for a self-intersection test; intersections are not tested directly.
+    fn concave_hull(points: &[Point<f64>], alpha: f64) -> Polygon<f64> {
+        if points.len() < 3 {
+            return Polygon::new(LineString::new(vec![]), vec![]);
+        }
+
+        let multi_point = MultiPoint::new(points.to_vec());
+        let hull = multi_point.convex_hull();
+        let mut boundary: Vec<Coord<f64>> = hull.exterior().coords().cloned().collect();
+        if boundary.last() == boundary.first() {
+            boundary.pop();
+        }
+
+        let mut interior: Vec<Coord<f64>> = points
+            .iter()
+            .map(|p| Coord { x: p.x(), y: p.y() })
+            .filter(|c| !boundary.contains(c))
+            .collect();
+
+        // Edges that failed a dig-in attempt this round (no valid interior
+        // point, or the replacement failed the length check): tracked by
+        // endpoint value rather than index, since successful dig-ins shift
+        // every later index. A rejected edge is skipped, not retried, so one
+        // edge near a sparse/clustered region can't freeze refinement of the
+        // rest of the hull.
+        let mut rejected: Vec<(Coord<f64>, Coord<f64>)> = Vec::new();
+
+        loop {
+            let n = boundary.len();
+            let mut longest_idx = None;
+            let mut longest_len = alpha;
+
+            for i in 0..n {
+                let a = boundary[i];
+                let b = boundary[(i + 1) % n];
+                if rejected.contains(&(a, b)) {
+                    continue;
+                }
+                let len = ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+                if len > longest_len {
+                    longest_len = len;
+                    longest_idx = Some(i);
+                }
+            }
+
+            let Some(i) = longest_idx else { break };
+            let a = boundary[i];
+            let b = boundary[(i + 1) % n];
+
+            let nearest = interior
+                .iter()
+                .filter(|&&c| c != a && c != b)
+                .min_by(|&&p, &&q| {
+                    let dp = (p.x - a.x).powi(2) + (p.y - a.y).powi(2)
+                        + (p.x - b.x).powi(2) + (p.y - b.y).powi(2);
+                    let dq = (q.x - a.x).powi(2) + (q.y - a.y).powi(2)
+                        + (q.x - b.x).powi(2) + (q.y - b.y).powi(2);
+                    dp.partial_cmp(&dq).unwrap_or(Ordering::Equal)
+                })
+                .cloned();
+
+            let Some(point) = nearest else {
+                rejected.push((a, b));
+                continue;
+            };
+
+            // Reject the dig-in if the two new edges together would be at
+            // least twice as long as the one they replace (a cheap proxy for
+            // avoiding self-intersection).
+            let new_len = ((point.x - a.x).powi(2) + (point.y - a.y).powi(2)).sqrt()
+                + ((b.x - point.x).powi(2) + (b.y - point.y).powi(2)).sqrt();
+            if new_len >= longest_len * 2.0 {
+                rejected.push((a, b));
+                continue;
+            }
+
+            boundary.insert(i + 1, point);
+            interior.retain(|&c| c != point);
+        }
+
+        boundary.push(boundary[0]);
+        Polygon::new(LineString::new(boundary), vec![])
+    }
 }
\ No newline at end of file
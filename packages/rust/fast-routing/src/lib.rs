@@ -12,17 +12,37 @@ pub mod utils;
 pub mod dummy_network;
 pub mod data_loader;
 pub mod error;
+pub mod geometry;
+pub mod route_optimizer;
+pub mod matrix;
+pub mod database;
+pub(crate) mod tsp;
 
 // Python bindings module (conditional compilation)
 #[cfg(feature = "python")]
 pub mod python_bindings;
 
+// Memory profiling instrumentation (conditional compilation)
+#[cfg(feature = "profiling")]
+pub mod profiling;
+
 pub use error::RoutingError;
 pub use network::{Network, Node, Edge, RoutingMode};
-pub use contraction::ContractionHierarchy;
-pub use isochrone::{IsochroneCalculator, IsochroneResult};
+pub use contraction::{ContractionHierarchy, MultiModalRouter};
+pub use isochrone::{CancellationToken, IsochroneCalculator, IsochroneEngine, IsochroneResult, SearchMode, TransitIsochroneResult};
 pub use dummy_network::DummyNetworkGenerator;
 pub use data_loader::NetworkLoader;
+pub use geometry::RouteGeometry;
+pub use route_optimizer::RouteOptimizer;
+pub use matrix::RoutingMatrix;
+pub use database::{DatabaseManager, PostgisConfig};
+
+#[cfg(feature = "profiling")]
+pub use profiling::{measure_ch_build, measure_isochrone, MemoryStats, Region};
+
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: profiling::InstrumentedAllocator = profiling::InstrumentedAllocator;
 
 /// Result type for the routing library
 pub type RoutingResult<T> = Result<T, RoutingError>;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use crate::{RoutingError, RoutingResult, contraction::ContractionHierarchy, network::{NodeId, Cost}};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// N-to-M travel-time matrix builder on top of an existing
+/// `ContractionHierarchy`, with no extra preprocessing beyond the hierarchy
+/// itself.
+pub struct RoutingMatrix;
+
+impl RoutingMatrix {
+    /// Compute `matrix[i][j]` as the travel cost from `sources[i]` to
+    /// `targets[j]`, using the standard bucket-based many-to-many CH
+    /// algorithm: a backward upward sweep (`ContractionHierarchy::upward_settle`)
+    /// from every target fills a bucket at each node it settles with
+    /// `(target_index, distance)`, then a forward upward sweep from each
+    /// source -- parallelized across sources the same way
+    /// `IsochroneCalculator::calculate_batch` parallelizes over start nodes
+    /// -- folds every settled node's bucket entries into
+    /// `matrix[source][target] = min(current, d_forward + d_back)`.
+    ///
+    /// Unreachable pairs stay `f64::INFINITY`.
+    pub fn compute(
+        ch: &ContractionHierarchy,
+        sources: &[NodeId],
+        targets: &[NodeId],
+    ) -> RoutingResult<Vec<Vec<Cost>>> {
+        for &source in sources {
+            if ch.original_network.get_node_index(source).is_none() {
+                return Err(RoutingError::Network(format!("Source node {} not found", source)));
+            }
+        }
+        for &target in targets {
+            if ch.original_network.get_node_index(target).is_none() {
+                return Err(RoutingError::Network(format!("Target node {} not found", target)));
+            }
+        }
+
+        let mut buckets: HashMap<NodeId, Vec<(u32, Cost)>> = HashMap::new();
+        for (target_index, &target) in targets.iter().enumerate() {
+            for (node, distance) in ch.upward_settle(target) {
+                buckets.entry(node).or_default().push((target_index as u32, distance));
+            }
+        }
+
+        let matrix = sources
+            .par_iter()
+            .map(|&source| {
+                let mut row = vec![Cost::INFINITY; targets.len()];
+                for (node, d_forward) in ch.upward_settle(source) {
+                    let Some(hits) = buckets.get(&node) else { continue };
+                    for &(target_index, d_back) in hits {
+                        let slot = &mut row[target_index as usize];
+                        let total = d_forward + d_back;
+                        if total < *slot {
+                            *slot = total;
+                        }
+                    }
+                }
+                row
+            })
+            .collect();
+
+        Ok(matrix)
+    }
+}
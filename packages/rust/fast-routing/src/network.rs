@@ -1,9 +1,13 @@
 use geo_types::{Point, LineString};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, BinaryHeap};
+use std::cmp::Ordering;
 use petgraph::graph::{NodeIndex, EdgeIndex, UnGraph};
+use petgraph::visit::EdgeRef;
 use petgraph::Graph;
+use rstar::{RTree, RTreeObject, PointDistance, AABB};
 use crate::{RoutingError, RoutingResult};
+use crate::utils::Utils;
 
 /// Node identifier type
 pub type NodeId = u64;
@@ -21,6 +25,14 @@ pub enum RoutingMode {
     Cycling,
     Car,
     Wheelchair,
+    /// Public transport, ridden only on edges that carry a service
+    /// `Edge::frequency` (see `Edge::with_frequency`). Unlike the other
+    /// modes, a single deterministic edge cost can't capture the expected
+    /// wait for one of several competing lines at a stop; see
+    /// `IsochroneCalculator::calculate_transit_isochrone` for the
+    /// frequency-aware hyperpath computation used instead of plain
+    /// Dijkstra for this mode.
+    Transit,
 }
 
 impl RoutingMode {
@@ -31,6 +43,7 @@ impl RoutingMode {
             RoutingMode::Cycling => 15.0,
             RoutingMode::Car => 50.0,
             RoutingMode::Wheelchair => 4.0,
+            RoutingMode::Transit => 25.0,
         }
     }
 }
@@ -71,6 +84,10 @@ pub struct Edge {
     pub oneway: bool,
     pub surface: Option<String>,
     pub highway_type: Option<String>,
+    /// Service frequency in vehicles/second for a transit line running this
+    /// edge, if any. Only set on genuine transit edges; its presence is what
+    /// makes an edge `is_accessible` for `RoutingMode::Transit`.
+    pub frequency: Option<f64>,
 }
 
 impl Edge {
@@ -92,9 +109,17 @@ impl Edge {
             oneway: false,
             surface: None,
             highway_type: None,
+            frequency: None,
         }
     }
 
+    /// Mark this edge as a transit line running at `frequency`
+    /// vehicles/second.
+    pub fn with_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = Some(frequency);
+        self
+    }
+
     /// Calculate cost for given routing mode and speed
     pub fn calculate_cost(&mut self, mode: RoutingMode, speed_kmh: Option<f64>) {
         let speed = speed_kmh.unwrap_or(mode.default_speed());
@@ -131,10 +156,56 @@ impl Edge {
                     !matches!(highway.as_str(), "steps" | "path")
                 })
             }
+            RoutingMode::Transit => self.frequency.is_some(),
         }
     }
 }
 
+/// Wrapper around a node's id and location for R-tree indexing
+#[derive(Debug, Clone, Copy)]
+struct SpatialNode {
+    node_id: NodeId,
+    lon: f64,
+    lat: f64,
+}
+
+impl RTreeObject for SpatialNode {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_point([self.lon, self.lat])
+    }
+}
+
+impl PointDistance for SpatialNode {
+    /// rstar requires a *squared* distance; callers use this for ordering
+    /// (nearest_neighbor) and as the radius test of locate_within_distance
+    /// (given a squared radius in meters), so we
+    /// return the squared Haversine distance in meters so results stay
+    /// geographically correct rather than degree-Euclidean.
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        let haversine = Self::haversine_meters(self.lon, self.lat, point[0], point[1]);
+        haversine * haversine
+    }
+}
+
+impl SpatialNode {
+    fn haversine_meters(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+        use std::f64::consts::PI;
+
+        let lat1_rad = lat1 * PI / 180.0;
+        let lat2_rad = lat2 * PI / 180.0;
+        let delta_lat = (lat2 - lat1) * PI / 180.0;
+        let delta_lon = (lon2 - lon1) * PI / 180.0;
+
+        let a = (delta_lat / 2.0).sin().powi(2)
+            + lat1_rad.cos() * lat2_rad.cos() * (delta_lon / 2.0).sin().powi(2);
+        let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+
+        6371000.0 * c
+    }
+}
+
 /// Main network structure for routing
 #[derive(Debug, Clone)]
 pub struct Network {
@@ -143,13 +214,20 @@ pub struct Network {
     pub edge_index_map: HashMap<EdgeId, EdgeIndex>,
     pub nodes: HashMap<NodeId, Node>,
     pub edges: HashMap<EdgeId, Edge>,
+    /// Spatial index over node locations, built on demand via `build_spatial_index`
+    spatial_index: Option<RTree<SpatialNode>>,
 }
 
-/// Edge with cost information for petgraph
+/// Reference to the backing `Edge` for a petgraph edge.
+///
+/// Costs used to be baked in per-mode at insertion time, which meant serving
+/// walking/cycling/car/wheelchair queries required four separate `Network`s.
+/// The petgraph edge now only carries the `edge_id`; `Network::edge_cost`
+/// resolves the actual cost for a given `RoutingMode` from `Edge::costs` at
+/// query time, so one loaded graph serves every mode.
 #[derive(Debug, Clone)]
 pub struct EdgeWithCost {
     pub edge_id: EdgeId,
-    pub cost: Cost,
 }
 
 impl Network {
@@ -161,9 +239,27 @@ impl Network {
             edge_index_map: HashMap::new(),
             nodes: HashMap::new(),
             edges: HashMap::new(),
+            spatial_index: None,
         }
     }
 
+    /// Build (or rebuild) the R-tree spatial index over all current nodes.
+    ///
+    /// Call this once after nodes have been inserted; `find_nearest_node`
+    /// uses the index when present and falls back to a linear scan
+    /// otherwise, while `find_nodes_within_radius` returns nothing without it.
+    pub fn build_spatial_index(&mut self) {
+        let entries: Vec<SpatialNode> = self.nodes.values()
+            .map(|node| SpatialNode {
+                node_id: node.id,
+                lon: node.location.x(),
+                lat: node.location.y(),
+            })
+            .collect();
+
+        self.spatial_index = Some(RTree::bulk_load(entries));
+    }
+
     /// Add a node to the network
     pub fn add_node(&mut self, node: Node) -> RoutingResult<()> {
         let node_id = node.id;
@@ -173,17 +269,22 @@ impl Network {
         Ok(())
     }
 
-    /// Add an edge to the network
+    /// Add an edge to the network, registering it under every routing mode
+    /// it permits (not just `mode`) so a single loaded graph can answer all
+    /// four profiles. `mode` only needs to name one mode that must have a
+    /// cost after this call; the rest are filled in opportunistically.
     pub fn add_edge(&mut self, mut edge: Edge, mode: RoutingMode) -> RoutingResult<()> {
-        // Calculate cost if not already set
-        if !edge.costs.contains_key(&mode) {
-            edge.calculate_cost(mode, None);
+        for candidate_mode in [RoutingMode::Walking, RoutingMode::Cycling, RoutingMode::Car, RoutingMode::Wheelchair] {
+            if !edge.costs.contains_key(&candidate_mode) {
+                edge.calculate_cost(candidate_mode, None);
+            }
         }
 
-        let cost = edge.get_cost(mode)
-            .ok_or_else(|| RoutingError::Network(
+        if !edge.costs.contains_key(&mode) {
+            return Err(RoutingError::Network(
                 format!("No cost calculated for mode {:?}", mode)
-            ))?;
+            ));
+        }
 
         // Get node indices
         let source_idx = self.node_index_map.get(&edge.source)
@@ -195,10 +296,10 @@ impl Network {
                 format!("Target node {} not found", edge.target)
             ))?;
 
-        // Add edge to graph
+        // Add edge to graph; the actual per-mode cost is resolved at query
+        // time via `edge_cost`, not baked in here.
         let edge_with_cost = EdgeWithCost {
             edge_id: edge.id,
-            cost,
         };
 
         let edge_index = self.graph.add_edge(*source_idx, *target_idx, edge_with_cost);
@@ -208,6 +309,17 @@ impl Network {
         Ok(())
     }
 
+    /// Resolve the travel cost of `edge_id` under `mode`, honoring
+    /// `Edge::is_accessible`. Returns `None` if the edge is forbidden for
+    /// `mode` or has no cost recorded for it.
+    pub fn edge_cost(&self, edge_id: EdgeId, mode: RoutingMode) -> Option<Cost> {
+        let edge = self.edges.get(&edge_id)?;
+        if !edge.is_accessible(mode) {
+            return None;
+        }
+        edge.get_cost(mode)
+    }
+
     /// Get node by ID
     pub fn get_node(&self, node_id: NodeId) -> Option<&Node> {
         self.nodes.get(&node_id)
@@ -224,7 +336,14 @@ impl Network {
     }
 
     /// Find nearest node to a given point
+    ///
+    /// Uses the R-tree spatial index when `build_spatial_index` has been
+    /// called; otherwise falls back to an O(n) linear scan.
     pub fn find_nearest_node(&self, point: &Point<f64>) -> Option<NodeId> {
+        if let Some(index) = &self.spatial_index {
+            return index.nearest_neighbor(&[point.x(), point.y()]).map(|n| n.node_id);
+        }
+
         let mut min_distance = f64::INFINITY;
         let mut nearest_node = None;
 
@@ -239,6 +358,46 @@ impl Network {
         nearest_node
     }
 
+    /// Find the graph node nearest to `point`.
+    ///
+    /// Ergonomic, GPS-coordinate-facing alias for `find_nearest_node`, for
+    /// callers (routing/isochrone entry points) that only have a raw lat/lon
+    /// and need a `NodeId` to hand to the rest of the API.
+    pub fn nearest_node(&self, point: &Point<f64>) -> Option<NodeId> {
+        self.find_nearest_node(point)
+    }
+
+    /// Find the graph node nearest to `point`, but only if it actually falls
+    /// within `radius_meters` of it.
+    ///
+    /// `SpatialNode::distance_2` already orders candidates by squared
+    /// Haversine meters rather than raw degrees, so the R-tree's nearest
+    /// match is geographically correct; this re-checks that match against
+    /// `Utils::haversine_distance` as the final, authoritative metric
+    /// distance before accepting it, and rejects it if the nearest node is
+    /// still farther away than `radius_meters` (e.g. a GPS point far from
+    /// any mapped road).
+    pub fn nearest_node_within(&self, point: &Point<f64>, radius_meters: f64) -> Option<NodeId> {
+        let node_id = self.nearest_node(point)?;
+        let node = self.nodes.get(&node_id)?;
+        let distance = Utils::haversine_distance(point, &node.location);
+        (distance <= radius_meters).then_some(node_id)
+    }
+
+    /// Find all nodes within `meters` of `point`, using the spatial index.
+    ///
+    /// Returns an empty vec if `build_spatial_index` has not been called yet.
+    pub fn find_nodes_within_radius(&self, point: &Point<f64>, meters: f64) -> Vec<NodeId> {
+        let Some(index) = &self.spatial_index else {
+            return Vec::new();
+        };
+
+        index
+            .locate_within_distance([point.x(), point.y()], meters * meters)
+            .map(|n| n.node_id)
+            .collect()
+    }
+
     /// Calculate Haversine distance between two points
     fn calculate_distance(&self, point1: &Point<f64>, point2: &Point<f64>) -> f64 {
         use std::f64::consts::PI;
@@ -270,6 +429,135 @@ impl Network {
         self.nodes.keys().cloned().collect()
     }
 
+    /// Compute connected components of the network, considering only edges
+    /// accessible to `mode`. Returns a map from node id to a component id;
+    /// nodes in the same component share the same id.
+    ///
+    /// The underlying graph is undirected, so connected components and
+    /// strongly connected components coincide here; `strongly_connected_components`
+    /// is provided as an alias for callers following the directed-graph naming
+    /// convention (e.g. a-b-street's `find_scc`).
+    pub fn connected_components(&self, mode: RoutingMode) -> HashMap<NodeId, usize> {
+        let mut component_of: HashMap<NodeId, usize> = HashMap::new();
+        let mut next_component = 0;
+
+        for &start_id in self.nodes.keys() {
+            if component_of.contains_key(&start_id) {
+                continue;
+            }
+
+            let mut stack = vec![start_id];
+            component_of.insert(start_id, next_component);
+
+            while let Some(node_id) = stack.pop() {
+                let Some(&node_idx) = self.node_index_map.get(&node_id) else { continue };
+
+                for edge_ref in self.graph.edges(node_idx) {
+                    let Some(edge) = self.edges.get(&edge_ref.weight().edge_id) else { continue };
+                    if !edge.is_accessible(mode) {
+                        continue;
+                    }
+
+                    let Some(neighbor_node) = self.graph.node_weight(edge_ref.target()) else { continue };
+                    let neighbor_id = neighbor_node.id;
+
+                    if !component_of.contains_key(&neighbor_id) {
+                        component_of.insert(neighbor_id, next_component);
+                        stack.push(neighbor_id);
+                    }
+                }
+            }
+
+            next_component += 1;
+        }
+
+        component_of
+    }
+
+    /// Alias for `connected_components`; see its docs for why the two
+    /// coincide on this undirected graph.
+    pub fn strongly_connected_components(&self, mode: RoutingMode) -> HashMap<NodeId, usize> {
+        self.connected_components(mode)
+    }
+
+    /// Extract the pruned subgraph containing only the largest connected
+    /// component for `mode`. Unreachable stub islands are dropped.
+    pub fn largest_component(&self, mode: RoutingMode) -> RoutingResult<Network> {
+        let component_of = self.connected_components(mode);
+
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for &component_id in component_of.values() {
+            *sizes.entry(component_id).or_insert(0) += 1;
+        }
+
+        let largest_id = sizes
+            .iter()
+            .max_by_key(|&(_, &count)| count)
+            .map(|(&id, _)| id)
+            .ok_or_else(|| RoutingError::Network("Network has no nodes".to_string()))?;
+
+        let mut pruned = Network::new();
+
+        for (&node_id, &component_id) in component_of.iter() {
+            if component_id != largest_id {
+                continue;
+            }
+            if let Some(node) = self.nodes.get(&node_id) {
+                pruned.add_node(node.clone())?;
+            }
+        }
+
+        for edge in self.edges.values() {
+            if component_of.get(&edge.source) == Some(&largest_id)
+                && component_of.get(&edge.target) == Some(&largest_id)
+            {
+                pruned.add_edge(edge.clone(), mode)?;
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Whether `node_id` belongs to the largest connected component for
+    /// `mode`. Returns `false` both for genuinely stranded nodes and for
+    /// unknown node ids.
+    pub fn is_in_main_component(&self, node_id: NodeId, mode: RoutingMode) -> bool {
+        let component_of = self.connected_components(mode);
+
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for &component_id in component_of.values() {
+            *sizes.entry(component_id).or_insert(0) += 1;
+        }
+
+        let Some(largest_id) = sizes.iter().max_by_key(|&(_, &count)| count).map(|(&id, _)| id) else {
+            return false;
+        };
+
+        component_of.get(&node_id) == Some(&largest_id)
+    }
+
+    /// IDs of every node that is *not* part of the largest connected
+    /// component for `mode` — the same set `ContractionHierarchy::new`
+    /// counts as "stranded" when it logs its fragmentation warning.
+    pub fn disconnected_nodes(&self, mode: RoutingMode) -> Vec<NodeId> {
+        let component_of = self.connected_components(mode);
+
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for &component_id in component_of.values() {
+            *sizes.entry(component_id).or_insert(0) += 1;
+        }
+
+        let Some(largest_id) = sizes.iter().max_by_key(|&(_, &count)| count).map(|(&id, _)| id) else {
+            return Vec::new();
+        };
+
+        component_of
+            .into_iter()
+            .filter(|&(_, component_id)| component_id != largest_id)
+            .map(|(node_id, _)| node_id)
+            .collect()
+    }
+
     /// Validate network consistency
     pub fn validate(&self) -> RoutingResult<()> {
         // Check if all edges reference existing nodes
@@ -301,4 +589,192 @@ impl Network {
 
         Ok(())
     }
+
+    /// Bidirectional A* search from `from_node_id` to `to_node_id`, using
+    /// `h(n) = haversine(n, target) / max_speed_of_mode` as an admissible
+    /// straight-line heuristic so the estimate stays in the same cost units
+    /// (seconds) as the edge weights and never overestimates.
+    ///
+    /// Gives a no-preprocessing routing option, and a correctness oracle to
+    /// check `ContractionHierarchy::shortest_path` results against.
+    pub fn astar_search(
+        &self,
+        from_node_id: NodeId,
+        to_node_id: NodeId,
+        mode: RoutingMode,
+        predecessor_mode: PredecessorMode,
+    ) -> RoutingResult<Option<(Cost, Vec<NodeId>)>> {
+        let from_idx = self.get_node_index(from_node_id)
+            .ok_or_else(|| RoutingError::Network(format!("Node {} not found", from_node_id)))?;
+        let to_idx = self.get_node_index(to_node_id)
+            .ok_or_else(|| RoutingError::Network(format!("Node {} not found", to_node_id)))?;
+
+        let target_location = self.nodes.get(&to_node_id).unwrap().location;
+        let source_location = self.nodes.get(&from_node_id).unwrap().location;
+        // Fastest speed any usable edge allows for `mode` (length / cost), so
+        // h(n) stays a lower bound where an edge was costed from a speed
+        // limit above `mode.default_speed()`.
+        let speed_mps = self.edges.values()
+            .filter(|edge| edge.is_accessible(mode))
+            .filter_map(|edge| edge.get_cost(mode).filter(|&cost| cost > 0.0).map(|cost| edge.length / cost))
+            .fold(mode.default_speed() * 1000.0 / 3600.0, f64::max);
+
+        let heuristic = |node_idx: NodeIndex, relative_to: &Point<f64>| -> Cost {
+            let location = self.graph.node_weight(node_idx).unwrap().location;
+            Utils::haversine_distance(&location, relative_to) / speed_mps
+        };
+
+        let mut g_fwd: HashMap<NodeIndex, Cost> = HashMap::new();
+        let mut g_bwd: HashMap<NodeIndex, Cost> = HashMap::new();
+        let mut pred_fwd: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut pred_bwd: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut settled_fwd: HashSet<NodeIndex> = HashSet::new();
+        let mut settled_bwd: HashSet<NodeIndex> = HashSet::new();
+
+        let mut open_fwd = BinaryHeap::new();
+        let mut open_bwd = BinaryHeap::new();
+
+        g_fwd.insert(from_idx, 0.0);
+        g_bwd.insert(to_idx, 0.0);
+        open_fwd.push(AStarState { node: from_idx, estimate: heuristic(from_idx, &target_location) });
+        open_bwd.push(AStarState { node: to_idx, estimate: heuristic(to_idx, &source_location) });
+
+        let mut best_cost = Cost::INFINITY;
+        let mut meeting_node: Option<NodeIndex> = None;
+
+        while !open_fwd.is_empty() || !open_bwd.is_empty() {
+            let fwd_best = open_fwd.peek().map(|s| s.estimate).unwrap_or(Cost::INFINITY);
+            let bwd_best = open_bwd.peek().map(|s| s.estimate).unwrap_or(Cost::INFINITY);
+
+            if fwd_best >= best_cost && bwd_best >= best_cost {
+                break;
+            }
+
+            // Expand whichever frontier is currently cheaper.
+            if fwd_best <= bwd_best {
+                let Some(AStarState { node, .. }) = open_fwd.pop() else { break };
+                if settled_fwd.contains(&node) {
+                    continue;
+                }
+                settled_fwd.insert(node);
+
+                if settled_bwd.contains(&node) {
+                    let total = g_fwd[&node] + g_bwd[&node];
+                    if total < best_cost {
+                        best_cost = total;
+                        meeting_node = Some(node);
+                    }
+                }
+
+                for edge in self.graph.edges(node) {
+                    let neighbor = edge.target();
+                    if settled_fwd.contains(&neighbor) {
+                        continue;
+                    }
+                    let Some(cost) = self.edge_cost(edge.weight().edge_id, mode) else { continue };
+                    let new_g = g_fwd[&node] + cost;
+                    if new_g < *g_fwd.get(&neighbor).unwrap_or(&Cost::INFINITY) {
+                        g_fwd.insert(neighbor, new_g);
+                        if matches!(predecessor_mode, PredecessorMode::Full) {
+                            pred_fwd.insert(neighbor, node);
+                        }
+                        open_fwd.push(AStarState { node: neighbor, estimate: new_g + heuristic(neighbor, &target_location) });
+                    }
+                }
+            } else {
+                let Some(AStarState { node, .. }) = open_bwd.pop() else { break };
+                if settled_bwd.contains(&node) {
+                    continue;
+                }
+                settled_bwd.insert(node);
+
+                if settled_fwd.contains(&node) {
+                    let total = g_fwd[&node] + g_bwd[&node];
+                    if total < best_cost {
+                        best_cost = total;
+                        meeting_node = Some(node);
+                    }
+                }
+
+                for edge in self.graph.edges(node) {
+                    let neighbor = edge.target();
+                    if settled_bwd.contains(&neighbor) {
+                        continue;
+                    }
+                    let Some(cost) = self.edge_cost(edge.weight().edge_id, mode) else { continue };
+                    let new_g = g_bwd[&node] + cost;
+                    if new_g < *g_bwd.get(&neighbor).unwrap_or(&Cost::INFINITY) {
+                        g_bwd.insert(neighbor, new_g);
+                        if matches!(predecessor_mode, PredecessorMode::Full) {
+                            pred_bwd.insert(neighbor, node);
+                        }
+                        open_bwd.push(AStarState { node: neighbor, estimate: new_g + heuristic(neighbor, &source_location) });
+                    }
+                }
+            }
+        }
+
+        let Some(meeting) = meeting_node else { return Ok(None) };
+
+        if matches!(predecessor_mode, PredecessorMode::CostOnly) {
+            return Ok(Some((best_cost, Vec::new())));
+        }
+
+        // Reconstruct: walk predecessors from the meeting node back to `from`,
+        // then forward from the meeting node to `to`.
+        let mut forward_half = vec![meeting];
+        let mut current = meeting;
+        while let Some(&pred) = pred_fwd.get(&current) {
+            forward_half.push(pred);
+            current = pred;
+        }
+        forward_half.reverse();
+
+        let mut backward_half = Vec::new();
+        let mut current = meeting;
+        while let Some(&pred) = pred_bwd.get(&current) {
+            backward_half.push(pred);
+            current = pred;
+        }
+
+        let mut path: Vec<NodeId> = forward_half
+            .into_iter()
+            .chain(backward_half)
+            .filter_map(|idx| self.graph.node_weight(idx).map(|n| n.id))
+            .collect();
+        path.dedup();
+
+        Ok(Some((best_cost, path)))
+    }
+}
+
+/// Controls how much work `Network::astar_search` does once it has found the
+/// optimal cost: `Full` reconstructs the path, `CostOnly` skips that work
+/// for callers that just need the travel time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredecessorMode {
+    Full,
+    CostOnly,
+}
+
+/// Priority-queue entry for `Network::astar_search`, ordered by `g + h`
+/// (min-heap via reversed comparison).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AStarState {
+    node: NodeIndex,
+    estimate: Cost,
+}
+
+impl Eq for AStarState {}
+
+impl Ord for AStarState {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.partial_cmp(&self.estimate).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for AStarState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
\ No newline at end of file
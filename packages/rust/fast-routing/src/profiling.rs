@@ -0,0 +1,117 @@
+//! Optional memory instrumentation, enabled by the `profiling` feature.
+//!
+//! Installs `InstrumentedAllocator` as the process's global allocator and
+//! exposes a `Region` type that reports bytes allocated, bytes deallocated,
+//! and peak resident bytes over its lifetime. `measure_ch_build` and
+//! `measure_isochrone` wrap the two operations most often blamed for memory
+//! blowups at 1M-10M edges, so callers don't have to open a `Region`
+//! themselves for the common case.
+
+use crate::network::{Cost, NodeId};
+use crate::{ContractionHierarchy, IsochroneCalculator, IsochroneResult, Network, RoutingMode, RoutingResult, SearchMode};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator that forwards to `std::alloc::System` while tracking
+/// total bytes allocated, total bytes deallocated, and the peak of
+/// `allocated - deallocated` seen anywhere in the process.
+pub struct InstrumentedAllocator;
+
+unsafe impl GlobalAlloc for InstrumentedAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            let deallocated = DEALLOCATED.load(Ordering::Relaxed);
+            PEAK.fetch_max(allocated.saturating_sub(deallocated), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        DEALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+    }
+}
+
+/// Allocator activity observed over the lifetime of a `Region`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryStats {
+    pub bytes_allocated: usize,
+    pub bytes_deallocated: usize,
+    pub peak_bytes: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    allocated: usize,
+    deallocated: usize,
+}
+
+fn snapshot() -> Snapshot {
+    Snapshot {
+        allocated: ALLOCATED.load(Ordering::Relaxed),
+        deallocated: DEALLOCATED.load(Ordering::Relaxed),
+    }
+}
+
+/// A measurement window over `InstrumentedAllocator`. `change()` reports
+/// allocator activity since the region was opened.
+///
+/// `peak_bytes` reflects the highest `allocated - deallocated` watermark
+/// seen anywhere in the process during the window, not just from this
+/// region's own allocations, since the allocator only tracks one process-wide
+/// peak; `Region::new` resets it to the current live-byte count so nested or
+/// back-to-back regions don't inherit a stale high-water mark from earlier
+/// unrelated work.
+pub struct Region {
+    start: Snapshot,
+}
+
+impl Region {
+    pub fn new() -> Self {
+        let start = snapshot();
+        PEAK.store(start.allocated.saturating_sub(start.deallocated), Ordering::Relaxed);
+        Self { start }
+    }
+
+    pub fn change(&self) -> MemoryStats {
+        let end = snapshot();
+        MemoryStats {
+            bytes_allocated: end.allocated.saturating_sub(self.start.allocated),
+            bytes_deallocated: end.deallocated.saturating_sub(self.start.deallocated),
+            peak_bytes: PEAK.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Region {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Build a `ContractionHierarchy` for `mode` over `network`, reporting the
+/// allocator activity of the build alongside the built hierarchy.
+pub fn measure_ch_build(network: Network, mode: RoutingMode) -> RoutingResult<(ContractionHierarchy, MemoryStats)> {
+    let region = Region::new();
+    let ch = ContractionHierarchy::new(network, mode)?;
+    Ok((ch, region.change()))
+}
+
+/// Calculate a single isochrone, reporting the allocator activity of the
+/// calculation alongside the result.
+pub fn measure_isochrone(
+    ch: &ContractionHierarchy,
+    start_node: NodeId,
+    max_cost: Cost,
+    mode: RoutingMode,
+) -> RoutingResult<(IsochroneResult, MemoryStats)> {
+    let region = Region::new();
+    let result = IsochroneCalculator::calculate(ch, start_node, max_cost, mode, SearchMode::Exact)?;
+    Ok((result, region.change()))
+}
@@ -1,12 +1,21 @@
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
-use crate::{NetworkLoader, ContractionHierarchy, IsochroneCalculator, IsochroneResult};
+use crate::{NetworkLoader, ContractionHierarchy, IsochroneCalculator, IsochroneResult, SearchMode, TransitIsochroneResult, Network, RoutingMode};
+use crate::utils::Utils;
+use geo_types::Point;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Python wrapper for the routing network
 #[pyclass]
 struct PyRoutingNetwork {
     ch: ContractionHierarchy,
+    /// Allocator activity of the contraction hierarchy build, captured with
+    /// `crate::profiling::Region` when the `profiling` feature is enabled.
+    #[cfg(feature = "profiling")]
+    build_memory_stats: crate::profiling::MemoryStats,
 }
 
 /// Python wrapper for isochrone results
@@ -19,6 +28,10 @@ struct PyIsochroneResult {
     max_cost: f64,
     #[pyo3(get)]
     reachable_nodes: usize,
+    #[pyo3(get)]
+    exact: bool,
+    #[pyo3(get)]
+    frontier_entries_discarded: usize,
     travel_costs: HashMap<u64, f64>,
 }
 
@@ -67,19 +80,106 @@ impl PyIsochroneResult {
     }
 }
 
+/// Python wrapper for `TransitIsochroneResult`
+#[pyclass]
+#[derive(Clone)]
+struct PyTransitIsochroneResult {
+    #[pyo3(get)]
+    start_node: u64,
+    #[pyo3(get)]
+    max_cost: f64,
+    #[pyo3(get)]
+    reachable_nodes: usize,
+    expected_cost: HashMap<u64, f64>,
+    attractive_lines: HashMap<u64, Vec<u64>>,
+}
+
+#[pymethods]
+impl PyTransitIsochroneResult {
+    /// Get all reachable nodes as a list
+    fn get_reachable_node_ids(&self) -> Vec<u64> {
+        self.expected_cost.keys().cloned().collect()
+    }
+
+    /// Get the expected cost of reaching `start_node` from each origin
+    fn get_node_costs(&self) -> HashMap<u64, f64> {
+        self.expected_cost.clone()
+    }
+
+    /// Get the attractive-line set (outgoing edge IDs) a traveller at
+    /// `node` should be willing to board
+    fn get_attractive_lines(&self, node: u64) -> Vec<u64> {
+        self.attractive_lines.get(&node).cloned().unwrap_or_default()
+    }
+}
+
+/// Python wrapper for a point-to-point route
+#[pyclass]
+#[derive(Clone)]
+struct PyRoute {
+    /// Ordered node IDs visited along the route
+    #[pyo3(get)]
+    nodes: Vec<u64>,
+    /// Total travel cost (seconds)
+    #[pyo3(get)]
+    total_cost: f64,
+    /// Route geometry, stitched from each traversed `Edge`'s `LineString`,
+    /// as a flat list of (longitude, latitude) coordinate pairs
+    #[pyo3(get)]
+    geometry: Vec<(f64, f64)>,
+}
+
 #[pymethods]
 impl PyRoutingNetwork {
     #[new]
     fn new(network_path: &str) -> PyResult<Self> {
-        let network = NetworkLoader::load_from_parquet(network_path)
+        let mut network = NetworkLoader::load_from_parquet(network_path)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
-        
-        let ch = ContractionHierarchy::new(network)
+        network.build_spatial_index();
+
+        #[cfg(feature = "profiling")]
+        let build_region = crate::profiling::Region::new();
+
+        let ch = ContractionHierarchy::new(network, RoutingMode::Walking)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
-        
-        Ok(PyRoutingNetwork { ch })
+
+        Ok(PyRoutingNetwork {
+            ch,
+            #[cfg(feature = "profiling")]
+            build_memory_stats: build_region.change(),
+        })
     }
-    
+
+    /// Load a network from `network_path`, reusing a previously-contracted
+    /// hierarchy cached under `cache_dir` when its stored digest still
+    /// matches `network_path`'s raw bytes, and rebuilding (then caching) it
+    /// otherwise. Use this instead of the constructor when querying the
+    /// same parquet file repeatedly, since contraction is the dominant cost
+    /// of a cold load.
+    #[staticmethod]
+    fn with_cache(network_path: &str, cache_dir: &str) -> PyResult<Self> {
+        let mut network = NetworkLoader::load_from_parquet(network_path)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))?;
+        network.build_spatial_index();
+
+        #[cfg(feature = "profiling")]
+        let build_region = crate::profiling::Region::new();
+
+        let ch = ContractionHierarchy::load_or_build_from_parquet(
+            std::path::Path::new(cache_dir),
+            network_path,
+            network,
+            RoutingMode::Walking,
+        )
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(PyRoutingNetwork {
+            ch,
+            #[cfg(feature = "profiling")]
+            build_memory_stats: build_region.change(),
+        })
+    }
+
     /// Get network statistics
     fn get_network_info(&self) -> PyResult<PyObject> {
         Python::with_gil(|py| {
@@ -94,66 +194,299 @@ impl PyRoutingNetwork {
     fn get_all_node_ids(&self) -> Vec<u64> {
         self.ch.original_network.get_all_node_ids()
     }
-    
-    /// Calculate isochrone from a starting point
-    fn calculate_isochrone(&self, start_node: u64, max_cost: f64) -> PyResult<PyIsochroneResult> {
-        match IsochroneCalculator::calculate(&self.ch, start_node, max_cost) {
+
+    /// Get bytes allocated, bytes deallocated, and peak resident bytes
+    /// measured while building this network's contraction hierarchy. Only
+    /// available when the crate is built with the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    fn get_memory_stats(&self) -> PyResult<PyObject> {
+        Python::with_gil(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("bytes_allocated", self.build_memory_stats.bytes_allocated)?;
+            dict.set_item("bytes_deallocated", self.build_memory_stats.bytes_deallocated)?;
+            dict.set_item("peak_bytes", self.build_memory_stats.peak_bytes)?;
+            Ok(dict.into())
+        })
+    }
+
+    /// Snap a raw `(lon, lat)` coordinate to the nearest network node via
+    /// the R-tree spatial index, returning its node id and the Haversine
+    /// distance to it in meters.
+    ///
+    /// Raises a `ValueError` if `max_snap_radius_m` is given and no node
+    /// falls within it, so a point far from any mapped road (e.g. a bad
+    /// geocode) fails loudly instead of silently snapping to whatever
+    /// happens to be nearest.
+    fn snap_to_nearest(&self, lon: f64, lat: f64, max_snap_radius_m: Option<f64>) -> PyResult<(u64, f64)> {
+        snap_point(&self.ch.original_network, lon, lat, max_snap_radius_m)
+    }
+
+    /// Snap a batch of `(lon, lat)` coordinates, in the same order as
+    /// `coords`. An entry is `None` wherever `snap_to_nearest` would have
+    /// raised, so callers (e.g. snapping a list of GTFS stops) can tell
+    /// which inputs failed without losing index alignment.
+    fn snap_many(&self, coords: Vec<(f64, f64)>, max_snap_radius_m: Option<f64>) -> Vec<Option<(u64, f64)>> {
+        coords
+            .into_iter()
+            .map(|(lon, lat)| snap_point(&self.ch.original_network, lon, lat, max_snap_radius_m).ok())
+            .collect()
+    }
+
+    /// Calculate an isochrone starting from the network node nearest to
+    /// `(lon, lat)`. See `snap_to_nearest` for the `max_snap_radius_m`
+    /// contract.
+    fn calculate_isochrone_from_coords(
+        &self,
+        lon: f64,
+        lat: f64,
+        max_cost: f64,
+        max_snap_radius_m: Option<f64>,
+    ) -> PyResult<PyIsochroneResult> {
+        let (start_node, _) = snap_point(&self.ch.original_network, lon, lat, max_snap_radius_m)?;
+        self.calculate_isochrone(start_node, max_cost, "exact", None)
+    }
+
+    /// Calculate a point-to-point route between the network nodes nearest
+    /// to `(start_lon, start_lat)` and `(end_lon, end_lat)`. See
+    /// `snap_to_nearest` for the `max_snap_radius_m` contract.
+    fn calculate_route_from_coords(
+        &self,
+        start_lon: f64,
+        start_lat: f64,
+        end_lon: f64,
+        end_lat: f64,
+        max_snap_radius_m: Option<f64>,
+    ) -> PyResult<PyRoute> {
+        let (start_node, _) = snap_point(&self.ch.original_network, start_lon, start_lat, max_snap_radius_m)?;
+        let (end_node, _) = snap_point(&self.ch.original_network, end_lon, end_lat, max_snap_radius_m)?;
+        self.calculate_route(start_node, end_node)
+    }
+
+    /// Calculate a point-to-point route, using the contraction hierarchy's
+    /// bidirectional rank-restricted search with shortcut unpacking.
+    ///
+    /// Returns a `PyRoute` with the ordered node list, the total cost, and
+    /// the route geometry stitched from each traversed edge's `LineString`.
+    /// Raises a `ValueError` if `start_node` and `end_node` are disconnected.
+    fn calculate_route(&self, start_node: u64, end_node: u64) -> PyResult<PyRoute> {
+        match self.ch.shortest_path(start_node, end_node, RoutingMode::Walking) {
+            Ok(Some((cost, path))) => Ok(build_py_route(&self.ch, cost, path)),
+            Ok(None) => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "No route found between node {} and node {}: nodes are disconnected",
+                start_node, end_node
+            ))),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())),
+        }
+    }
+
+    /// Calculate isochrone from a starting point.
+    ///
+    /// `search_mode` is one of `"exact"` (default), `"greedy"`, or
+    /// `"beam"` (requires `beam_width`); see `SearchMode` for what the
+    /// approximate modes trade off.
+    #[pyo3(signature = (start_node, max_cost, search_mode="exact", beam_width=None))]
+    fn calculate_isochrone(
+        &self,
+        start_node: u64,
+        max_cost: f64,
+        search_mode: &str,
+        beam_width: Option<usize>,
+    ) -> PyResult<PyIsochroneResult> {
+        let search_mode = parse_search_mode(search_mode, beam_width)?;
+        match IsochroneCalculator::calculate(&self.ch, start_node, max_cost, crate::RoutingMode::Walking, search_mode) {
             Ok(result) => Ok(convert_isochrone_result(result, start_node, max_cost)),
             Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
         }
     }
     
-    /// Calculate multiple isochrones from different starting points
-    fn calculate_multiple_isochrones(&self, start_nodes: Vec<u64>, max_cost: f64) -> PyResult<Vec<PyIsochroneResult>> {
-        let mut results = Vec::new();
-        
-        for &start_node in &start_nodes {
-            match IsochroneCalculator::calculate(&self.ch, start_node, max_cost) {
-                Ok(result) => results.push(convert_isochrone_result(result, start_node, max_cost)),
-                Err(e) => {
-                    eprintln!("Warning: Failed to calculate isochrone for node {}: {}", start_node, e);
-                    continue;
-                }
-            }
+    /// Calculate a transit isochrone using the frequency-aware hyperpath
+    /// rule (see `IsochroneCalculator::calculate_transit_isochrone`).
+    /// `start_node` acts as the destination: `expected_cost` is the cost of
+    /// reaching it by transit from each origin, not the other way around.
+    fn calculate_transit_isochrone(&self, start_node: u64, max_cost: f64) -> PyResult<PyTransitIsochroneResult> {
+        match IsochroneCalculator::calculate_transit_isochrone(&self.ch, start_node, max_cost) {
+            Ok(result) => Ok(convert_transit_isochrone_result(result)),
+            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())),
         }
-        
-        Ok(results)
     }
-    
-    /// Calculate isochrones with multiple time thresholds
-    fn calculate_isochrone_multiple_times(&self, start_node: u64, time_thresholds: Vec<f64>) -> PyResult<Vec<PyIsochroneResult>> {
-        let mut results = Vec::new();
-        
-        for &max_cost in &time_thresholds {
-            match IsochroneCalculator::calculate(&self.ch, start_node, max_cost) {
-                Ok(result) => results.push(convert_isochrone_result(result, start_node, max_cost)),
-                Err(e) => {
-                    eprintln!("Warning: Failed to calculate isochrone for time {}: {}", max_cost, e);
-                    continue;
-                }
-            }
-        }
-        
-        Ok(results)
+
+    /// Calculate multiple isochrones from different starting points, in
+    /// parallel across a rayon thread pool. `progress_callback`, if not
+    /// `None`, is called periodically with `(completed, total)`; returning
+    /// `False` from it cancels the remaining work. `search_mode`/`beam_width`
+    /// behave as in `calculate_isochrone`.
+    #[pyo3(signature = (start_nodes, max_cost, progress_callback=None, search_mode="exact", beam_width=None))]
+    fn calculate_multiple_isochrones(
+        &self,
+        py: Python<'_>,
+        start_nodes: Vec<u64>,
+        max_cost: f64,
+        progress_callback: Option<PyObject>,
+        search_mode: &str,
+        beam_width: Option<usize>,
+    ) -> PyResult<Vec<PyIsochroneResult>> {
+        let search_mode = parse_search_mode(search_mode, beam_width)?;
+        run_isochrone_batch(&self.ch, &start_nodes, &[max_cost], RoutingMode::Walking, search_mode, py, progress_callback)
     }
-    
-    /// Batch calculate isochrones for multiple start points and time thresholds
-    fn calculate_batch_isochrones(&self, start_nodes: Vec<u64>, time_thresholds: Vec<f64>) -> PyResult<Vec<PyIsochroneResult>> {
-        let mut results = Vec::new();
-        
-        for &start_node in &start_nodes {
-            for &max_cost in &time_thresholds {
-                match IsochroneCalculator::calculate(&self.ch, start_node, max_cost) {
-                    Ok(result) => results.push(convert_isochrone_result(result, start_node, max_cost)),
-                    Err(e) => {
-                        eprintln!("Warning: Failed to calculate isochrone for node {} at time {}: {}", start_node, max_cost, e);
-                        continue;
+
+    /// Calculate isochrones with multiple time thresholds, in parallel
+    /// across a rayon thread pool. See `calculate_multiple_isochrones` for
+    /// the `progress_callback`/`search_mode`/`beam_width` contract.
+    #[pyo3(signature = (start_node, time_thresholds, progress_callback=None, search_mode="exact", beam_width=None))]
+    fn calculate_isochrone_multiple_times(
+        &self,
+        py: Python<'_>,
+        start_node: u64,
+        time_thresholds: Vec<f64>,
+        progress_callback: Option<PyObject>,
+        search_mode: &str,
+        beam_width: Option<usize>,
+    ) -> PyResult<Vec<PyIsochroneResult>> {
+        let search_mode = parse_search_mode(search_mode, beam_width)?;
+        run_isochrone_batch(&self.ch, &[start_node], &time_thresholds, RoutingMode::Walking, search_mode, py, progress_callback)
+    }
+
+    /// Batch calculate isochrones for the cartesian product of start points
+    /// and time thresholds, in parallel across a rayon thread pool. See
+    /// `calculate_multiple_isochrones` for the
+    /// `progress_callback`/`search_mode`/`beam_width` contract.
+    #[pyo3(signature = (start_nodes, time_thresholds, progress_callback=None, search_mode="exact", beam_width=None))]
+    fn calculate_batch_isochrones(
+        &self,
+        py: Python<'_>,
+        start_nodes: Vec<u64>,
+        time_thresholds: Vec<f64>,
+        progress_callback: Option<PyObject>,
+        search_mode: &str,
+        beam_width: Option<usize>,
+    ) -> PyResult<Vec<PyIsochroneResult>> {
+        let search_mode = parse_search_mode(search_mode, beam_width)?;
+        run_isochrone_batch(&self.ch, &start_nodes, &time_thresholds, RoutingMode::Walking, search_mode, py, progress_callback)
+    }
+}
+
+/// Run the cartesian product of `start_nodes` x `cost_thresholds` through
+/// `IsochroneCalculator::calculate_batch_streaming`, while the calling
+/// (GIL-holding) thread drains its progress channel roughly every 200ms to
+/// report `(completed, total)` to `progress_callback` and check for
+/// cancellation. This is the same sized thread pool, scratch-buffer reuse,
+/// and `CancellationToken` the Rust API uses for batch isochrones, instead
+/// of a second hand-rolled implementation.
+///
+/// Failed individual jobs are logged and dropped, matching the previous
+/// behavior; returning `False` from `progress_callback` stops the batch from
+/// picking up further start nodes (already in-flight nodes still finish all
+/// their thresholds), so the result is a partial subset of the cartesian
+/// product; start nodes run in parallel, so the skipped ones need not be
+/// the last in `start_nodes`.
+fn run_isochrone_batch(
+    ch: &ContractionHierarchy,
+    start_nodes: &[u64],
+    cost_thresholds: &[f64],
+    mode: RoutingMode,
+    search_mode: SearchMode,
+    py: Python<'_>,
+    progress_callback: Option<PyObject>,
+) -> PyResult<Vec<PyIsochroneResult>> {
+    let total = start_nodes.len() * cost_thresholds.len();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let cancel: crate::CancellationToken = Arc::new(AtomicBool::new(false));
+
+    let results = std::thread::scope(|scope| {
+        let cancel = &cancel;
+        let handle = scope.spawn(move || {
+            IsochroneCalculator::calculate_batch_streaming(
+                ch,
+                start_nodes,
+                cost_thresholds,
+                mode,
+                search_mode,
+                None,
+                Some(tx),
+                Some(cancel),
+            )
+        });
+
+        let mut completed = 0usize;
+        while completed < total {
+            match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(_) => completed += 1,
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                    if handle.is_finished() {
+                        break;
                     }
                 }
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+            }
+
+            if let Some(callback) = &progress_callback {
+                let keep_going: bool = callback
+                    .call1(py, (completed, total))
+                    .and_then(|r| r.extract(py))
+                    .unwrap_or(true);
+                if !keep_going {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+
+        handle.join().expect("isochrone batch worker thread panicked")
+    }).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+    let mut converted = Vec::new();
+    for (start_node, per_threshold) in results {
+        for (&max_cost, result) in cost_thresholds.iter().zip(per_threshold) {
+            match result {
+                Ok(result) => converted.push(convert_isochrone_result(result, start_node, max_cost)),
+                Err(e) => eprintln!(
+                    "Warning: Failed to calculate isochrone for node {} at time {}: {}",
+                    start_node, max_cost, e
+                ),
             }
         }
-        
-        Ok(results)
+    }
+
+    Ok(converted)
+}
+
+/// Snap `(lon, lat)` to the nearest node in `network` via its R-tree spatial
+/// index, returning the node id and the Haversine distance to it in meters.
+/// Errors if `max_snap_radius_m` is given and the nearest node is farther
+/// away than it, or if the network has no nodes at all.
+fn snap_point(network: &Network, lon: f64, lat: f64, max_snap_radius_m: Option<f64>) -> PyResult<(u64, f64)> {
+    let point = Point::new(lon, lat);
+    let node_id = match max_snap_radius_m {
+        Some(radius) => network.nearest_node_within(&point, radius),
+        None => network.nearest_node(&point),
+    }
+    .ok_or_else(|| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(match max_snap_radius_m {
+            Some(radius) => format!("No network node found within {} m of ({}, {})", radius, lon, lat),
+            None => format!("No network node found near ({}, {})", lon, lat),
+        })
+    })?;
+
+    let node = network.get_node(node_id)
+        .expect("nearest_node returned a node id not present in the network");
+    let distance = Utils::haversine_distance(&point, &node.location);
+    Ok((node_id, distance))
+}
+
+/// Parse the Python-facing `search_mode` string into a `SearchMode`.
+/// `"beam"` requires `beam_width`; any other value is rejected.
+fn parse_search_mode(search_mode: &str, beam_width: Option<usize>) -> PyResult<SearchMode> {
+    match search_mode {
+        "exact" => Ok(SearchMode::Exact),
+        "greedy" => Ok(SearchMode::Greedy),
+        "beam" => {
+            let width = beam_width.ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>("search_mode=\"beam\" requires beam_width")
+            })?;
+            Ok(SearchMode::Beam { width })
+        }
+        other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown search_mode {:?}; expected \"exact\", \"greedy\", or \"beam\"", other
+        ))),
     }
 }
 
@@ -163,16 +496,71 @@ fn convert_isochrone_result(result: IsochroneResult, start_node: u64, max_cost:
         start_node,
         max_cost,
         reachable_nodes: result.reachable_nodes,
+        exact: result.exact,
+        frontier_entries_discarded: result.frontier_entries_discarded,
         travel_costs: result.travel_costs,
     }
 }
 
+/// Convert Rust `TransitIsochroneResult` to its Python wrapper
+fn convert_transit_isochrone_result(result: TransitIsochroneResult) -> PyTransitIsochroneResult {
+    PyTransitIsochroneResult {
+        start_node: result.start_node,
+        max_cost: result.max_cost,
+        reachable_nodes: result.reachable_nodes,
+        expected_cost: result.expected_cost,
+        attractive_lines: result.attractive_lines,
+    }
+}
+
+/// Stitch the `LineString` geometry of each edge traversed by `path` into one
+/// flat coordinate list, orienting each edge's coordinates to match the
+/// direction it was traversed in and dropping the duplicate shared endpoint
+/// between consecutive edges.
+fn build_py_route(ch: &ContractionHierarchy, cost: f64, path: Vec<u64>) -> PyRoute {
+    let mut geometry: Vec<(f64, f64)> = Vec::new();
+
+    for pair in path.windows(2) {
+        let Some(edge) = ch.original_network.edges.values().find(|e| {
+            (e.source == pair[0] && e.target == pair[1]) || (e.source == pair[1] && e.target == pair[0])
+        }) else {
+            continue;
+        };
+
+        let coords: Vec<(f64, f64)> = edge.geometry.coords().map(|c| (c.x, c.y)).collect();
+        let ordered: Vec<(f64, f64)> = if edge.source == pair[1] {
+            coords.into_iter().rev().collect()
+        } else {
+            coords
+        };
+
+        if geometry.last() == ordered.first() {
+            geometry.extend(ordered.into_iter().skip(1));
+        } else {
+            geometry.extend(ordered);
+        }
+    }
+
+    PyRoute {
+        nodes: path,
+        total_cost: cost,
+        geometry,
+    }
+}
+
 /// Load a routing network from parquet file
 #[pyfunction]
 fn load_network(network_path: &str) -> PyResult<PyRoutingNetwork> {
     PyRoutingNetwork::new(network_path)
 }
 
+/// Load a routing network from parquet file, reusing a cached contraction
+/// hierarchy under `cache_dir` when it is still valid for this network.
+#[pyfunction]
+fn load_network_cached(network_path: &str, cache_dir: &str) -> PyResult<PyRoutingNetwork> {
+    PyRoutingNetwork::with_cache(network_path, cache_dir)
+}
+
 /// Get random sample of node IDs from the network
 #[pyfunction]
 fn get_random_nodes(network: &PyRoutingNetwork, sample_size: usize) -> Vec<u64> {
@@ -192,7 +580,10 @@ fn get_random_nodes(network: &PyRoutingNetwork, sample_size: usize) -> Vec<u64>
 fn fast_routing_py(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyRoutingNetwork>()?;
     m.add_class::<PyIsochroneResult>()?;
+    m.add_class::<PyTransitIsochroneResult>()?;
+    m.add_class::<PyRoute>()?;
     m.add_function(wrap_pyfunction!(load_network, m)?)?;
+    m.add_function(wrap_pyfunction!(load_network_cached, m)?)?;
     m.add_function(wrap_pyfunction!(get_random_nodes, m)?)?;
     
     // Add module metadata
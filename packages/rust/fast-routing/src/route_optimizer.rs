@@ -0,0 +1,112 @@
+use crate::{RoutingError, RoutingResult, contraction::ContractionHierarchy, network::{NodeId, Cost, RoutingMode}};
+use crate::tsp;
+
+/// Multi-stop trip planner built on top of `ContractionHierarchy::shortest_path`.
+///
+/// Given a set of waypoints, `RouteOptimizer` treats pairwise shortest-path
+/// costs as a black-box oracle, builds a full cost matrix between them, seeds
+/// a tour with nearest-neighbor, and improves it with 2-opt. This turns the
+/// crate's single-pair routing into a practical multi-stop trip planner
+/// without needing its own notion of "distance" beyond what the hierarchy
+/// already computes.
+pub struct RouteOptimizer<'a> {
+    hierarchy: &'a ContractionHierarchy,
+    mode: RoutingMode,
+}
+
+impl<'a> RouteOptimizer<'a> {
+    /// Wrap a hierarchy; `mode` is forwarded to every `shortest_path` call
+    /// used to build the cost matrix and the final concatenated path.
+    pub fn new(hierarchy: &'a ContractionHierarchy, mode: RoutingMode) -> Self {
+        Self { hierarchy, mode }
+    }
+
+    /// Compute the cheapest order to visit `waypoints`.
+    ///
+    /// When `open_tour` is `false`, the optimized order is treated as a loop
+    /// (the tour cost includes returning from the last waypoint to the
+    /// first, and the returned full path closes the loop); when `true`, the
+    /// tour ends at the last waypoint instead. `max_iterations` bounds the
+    /// number of full 2-opt improvement passes, guarding against pathological
+    /// inputs where improving swaps keep appearing.
+    ///
+    /// Returns the reordered waypoints, the total cost, and the concatenated
+    /// full path (shared nodes at waypoint boundaries are not duplicated).
+    pub fn optimize(
+        &self,
+        waypoints: &[NodeId],
+        open_tour: bool,
+        max_iterations: usize,
+    ) -> RoutingResult<(Vec<NodeId>, Cost, Vec<NodeId>)> {
+        if waypoints.len() < 2 {
+            return Err(RoutingError::ContractionHierarchy(
+                "RouteOptimizer requires at least 2 waypoints".to_string(),
+            ));
+        }
+
+        let matrix = self.cost_matrix(waypoints)?;
+
+        let mut order = tsp::nearest_neighbor_order(waypoints.len(), &matrix, None);
+        tsp::two_opt_improve(&mut order, &matrix, None, None, !open_tour, max_iterations);
+
+        let waypoint_order: Vec<NodeId> = order.iter().map(|&i| waypoints[i]).collect();
+
+        let mut full_sequence = waypoint_order.clone();
+        if !open_tour {
+            full_sequence.push(waypoint_order[0]);
+        }
+
+        let (total_cost, full_path) = self.concatenate_path(&full_sequence)?;
+
+        Ok((waypoint_order, total_cost, full_path))
+    }
+
+    /// Build an N x N cost matrix between `waypoints` using `shortest_path`
+    /// as the pairwise-cost oracle.
+    fn cost_matrix(&self, waypoints: &[NodeId]) -> RoutingResult<Vec<Vec<Cost>>> {
+        let n = waypoints.len();
+        let mut matrix = vec![vec![0.0; n]; n];
+
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let (cost, _) = self.hierarchy.shortest_path(waypoints[i], waypoints[j], self.mode)?.ok_or_else(|| {
+                    RoutingError::ContractionHierarchy(format!(
+                        "No path found between waypoints {} and {}",
+                        waypoints[i], waypoints[j]
+                    ))
+                })?;
+                matrix[i][j] = cost;
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Run `shortest_path` between each consecutive pair of `sequence` and
+    /// concatenate the results into one total cost and one full path.
+    fn concatenate_path(&self, sequence: &[NodeId]) -> RoutingResult<(Cost, Vec<NodeId>)> {
+        let mut total_cost = 0.0;
+        let mut full_path: Vec<NodeId> = Vec::new();
+
+        for pair in sequence.windows(2) {
+            let (cost, path) = self.hierarchy.shortest_path(pair[0], pair[1], self.mode)?.ok_or_else(|| {
+                RoutingError::ContractionHierarchy(format!(
+                    "No path found between waypoints {} and {}",
+                    pair[0], pair[1]
+                ))
+            })?;
+
+            total_cost += cost;
+            if full_path.last() == path.first() {
+                full_path.extend_from_slice(&path[1..]);
+            } else {
+                full_path.extend_from_slice(&path);
+            }
+        }
+
+        Ok((total_cost, full_path))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::network::Cost;
+
+/// Shared TSP building blocks (nearest-neighbor seeding, 2-opt local search)
+/// used by both `ContractionHierarchy::optimize_order` (fixed start/end over
+/// a matrix of stops) and `RouteOptimizer::optimize` (a free open/closed
+/// tour). Callers that have no start/end cost to fold in (a free tour) just
+/// pass `None` for `entry`/`exit`.
+
+/// Build a nearest-neighbor tour over `0..n`. If `entry` is given, the first
+/// stop is the one cheapest to reach from the implicit starting point
+/// (`entry[i]` is the cost of entering stop `i` first); otherwise the tour
+/// starts from index 0.
+pub(crate) fn nearest_neighbor_order(n: usize, matrix: &[Vec<Cost>], entry: Option<&[Cost]>) -> Vec<usize> {
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    let first = match entry {
+        Some(entry) => (0..n).min_by(|&a, &b| entry[a].partial_cmp(&entry[b]).unwrap()).unwrap(),
+        None => 0,
+    };
+    visited[first] = true;
+    order.push(first);
+    let mut current = first;
+
+    for _ in 1..n {
+        let next = (0..n)
+            .filter(|&j| !visited[j])
+            .min_by(|&a, &b| matrix[current][a].partial_cmp(&matrix[current][b]).unwrap())
+            .unwrap();
+        visited[next] = true;
+        order.push(next);
+        current = next;
+    }
+
+    order
+}
+
+/// Total cost of visiting `order` (indices into `matrix`) in sequence.
+/// `entry[order[0]]` (if given) is added for the cost of reaching the first
+/// stop from outside the matrix; when `closed` is true, the cost of
+/// returning from the last stop to the first is added, otherwise
+/// `exit[order.last()]` (if given) is added for the cost of leaving the
+/// matrix from the last stop.
+pub(crate) fn tour_cost(order: &[usize], matrix: &[Vec<Cost>], entry: Option<&[Cost]>, exit: Option<&[Cost]>, closed: bool) -> Cost {
+    let mut total: Cost = order.windows(2).map(|pair| matrix[pair[0]][pair[1]]).sum();
+    if let Some(entry) = entry {
+        total += entry[order[0]];
+    }
+    if closed && order.len() > 1 {
+        total += matrix[*order.last().unwrap()][order[0]];
+    } else if let Some(exit) = exit {
+        total += exit[*order.last().unwrap()];
+    }
+    total
+}
+
+/// Repeatedly reverse a segment `[i..=j]` of `order` while doing so lowers
+/// total tour cost (per `tour_cost`), until no improving swap exists or
+/// `max_iterations` full passes have run.
+pub(crate) fn two_opt_improve(
+    order: &mut [usize],
+    matrix: &[Vec<Cost>],
+    entry: Option<&[Cost]>,
+    exit: Option<&[Cost]>,
+    closed: bool,
+    max_iterations: usize,
+) {
+    let n = order.len();
+    let mut improved = true;
+    let mut iterations = 0;
+
+    while improved && iterations < max_iterations {
+        improved = false;
+        for i in 0..n.saturating_sub(1) {
+            for j in (i + 1)..n {
+                let before = tour_cost(order, matrix, entry, exit, closed);
+                order[i..=j].reverse();
+                let after = tour_cost(order, matrix, entry, exit, closed);
+
+                if after < before {
+                    improved = true;
+                } else {
+                    order[i..=j].reverse();
+                }
+            }
+        }
+        iterations += 1;
+    }
+}
+
+/// Enumerate all permutations of `0..n` and return the cheapest tour (per
+/// `tour_cost`). Only practical for small `n`.
+pub(crate) fn best_order_exhaustive(n: usize, matrix: &[Vec<Cost>], entry: Option<&[Cost]>, exit: Option<&[Cost]>, closed: bool) -> Vec<usize> {
+    use permutohedron::LexicalPermutation;
+
+    let mut indices: Vec<usize> = (0..n).collect();
+    let mut best_order = indices.clone();
+    let mut best_cost = tour_cost(&indices, matrix, entry, exit, closed);
+
+    while indices.next_permutation() {
+        let cost = tour_cost(&indices, matrix, entry, exit, closed);
+        if cost < best_cost {
+            best_cost = cost;
+            best_order = indices.clone();
+        }
+    }
+
+    best_order
+}
@@ -1,4 +1,4 @@
-use fast_routing::{DummyNetworkGenerator, ContractionHierarchy, IsochroneCalculator};
+use fast_routing::{DummyNetworkGenerator, ContractionHierarchy, IsochroneCalculator, SearchMode, DatabaseManager, Network, Node, Edge, RoutingMatrix};
 
 #[test]
 fn test_basic_routing() {
@@ -6,13 +6,13 @@ fn test_basic_routing() {
     assert_eq!(network.node_count(), 9);
     assert_eq!(network.edge_count(), 12);
     
-    let ch = ContractionHierarchy::new(network).unwrap();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
     let stats = ch.stats();
     assert_eq!(stats["nodes"], "9");
     assert_eq!(stats["edges"], "12");
     
     // Test shortest path
-    let result = ch.shortest_path(0, 8).unwrap();
+    let result = ch.shortest_path(0, 8, fast_routing::RoutingMode::Car).unwrap();
     assert!(result.is_some());
     
     let (cost, path) = result.unwrap();
@@ -27,30 +27,180 @@ fn test_grid_network() {
     let network = DummyNetworkGenerator::create_grid(3, 3, 100.0).unwrap();
     assert_eq!(network.node_count(), 9);
     
-    let ch = ContractionHierarchy::new(network).unwrap();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
     
     // Test that we can route between corners
-    let result = ch.shortest_path(0, 8).unwrap();
+    let result = ch.shortest_path(0, 8, fast_routing::RoutingMode::Car).unwrap();
     assert!(result.is_some());
 }
 
 #[test]
 fn test_isochrone() {
     let network = DummyNetworkGenerator::create_simple().unwrap();
-    let ch = ContractionHierarchy::new(network).unwrap();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
     
-    let isochrone = IsochroneCalculator::calculate(&ch, 0, 300.0).unwrap();
+    let isochrone = IsochroneCalculator::calculate(&ch, 0, 300.0, fast_routing::RoutingMode::Car, SearchMode::Exact).unwrap();
     assert_eq!(isochrone.max_cost, 300.0);
     // With 300s limit and ~72s per edge, we should reach all 9 nodes in a 3x3 grid
     assert_eq!(isochrone.reachable_nodes, 9);
 }
 
+#[test]
+fn test_isochrone_to_polygon_has_no_duplicate_vertices() {
+    let network = DummyNetworkGenerator::create_grid(5, 5, 100.0).unwrap();
+    let ch = ContractionHierarchy::new(network.clone(), fast_routing::RoutingMode::Car).unwrap();
+
+    // Center of a 5x5 grid, large enough radius to reach a good chunk of it.
+    let isochrone = IsochroneCalculator::calculate(&ch, 12, 1000.0, fast_routing::RoutingMode::Car, SearchMode::Exact).unwrap();
+    assert!(isochrone.reachable_nodes >= 9);
+
+    let polygon = isochrone.to_polygon(&network, isochrone.max_cost);
+    let coords: Vec<_> = polygon.exterior().coords().cloned().collect();
+    assert!(coords.len() >= 4, "a closed polygon needs at least 3 distinct vertices plus the closing point");
+
+    // A stale/reused interior point used to show up as two consecutive
+    // identical vertices in the ring.
+    for pair in coords.windows(2) {
+        assert_ne!(pair[0], pair[1], "concave_hull produced a degenerate repeated vertex");
+    }
+}
+
+#[test]
+fn test_isochrone_to_polygon_empty_for_too_few_points() {
+    let network = DummyNetworkGenerator::create_simple().unwrap();
+    let ch = ContractionHierarchy::new(network.clone(), fast_routing::RoutingMode::Car).unwrap();
+
+    // max_cost of 0 only reaches the start node itself, too few points for a hull.
+    let isochrone = IsochroneCalculator::calculate(&ch, 0, 0.0, fast_routing::RoutingMode::Car, SearchMode::Exact).unwrap();
+    let polygon = isochrone.to_polygon(&network, isochrone.max_cost);
+    assert_eq!(polygon.exterior().coords().count(), 0);
+}
+
+#[test]
+fn test_revert_to_undoes_operations_log() {
+    let mut db = DatabaseManager::new().unwrap();
+
+    let op_node1 = db.add_node(&Node::new(1, 10.0, 50.0)).unwrap();
+    db.add_node(&Node::new(2, 10.1, 50.0)).unwrap();
+
+    let geometry = geo_types::LineString::new(vec![
+        geo_types::Coord { x: 10.0, y: 50.0 },
+        geo_types::Coord { x: 10.1, y: 50.0 },
+    ]);
+    let edge = Edge::new(1, 1, 2, geometry, 100.0);
+    let op_before_update = db.apply_edge_update(&edge).unwrap();
+
+    let mut edge_v2 = edge.clone();
+    edge_v2.max_speed = Some(30.0);
+    db.apply_edge_update(&edge_v2).unwrap();
+
+    let updated = db.load_network(fast_routing::RoutingMode::Car).unwrap();
+    assert_eq!(updated.edges.get(&1).unwrap().max_speed, Some(30.0));
+
+    // Revert past the second update: edge 1 should go back to having no max_speed.
+    db.revert_to(op_before_update).unwrap();
+    let reverted = db.load_network(fast_routing::RoutingMode::Car).unwrap();
+    assert_eq!(reverted.edges.get(&1).unwrap().max_speed, None);
+
+    // Revert to right after node 1 was added: node 2 and the edge should be gone.
+    db.revert_to(op_node1).unwrap();
+    let fully_reverted = db.load_network(fast_routing::RoutingMode::Car).unwrap();
+    assert!(fully_reverted.nodes.contains_key(&1));
+    assert!(!fully_reverted.nodes.contains_key(&2));
+    assert!(fully_reverted.edges.get(&1).is_none());
+}
+
+/// Builds a 1 -- 2 -- 3 -- 4 line, where the first two edges are transit
+/// lines (with a service frequency) and the last is a plain car edge with
+/// no frequency, so node 4 is physically connected but never transit-reachable.
+fn transit_chain_network() -> Network {
+    let mut network = Network::new();
+    for (id, lon) in [(1u64, 0.0), (2, 0.01), (3, 0.02), (4, 0.03)] {
+        network.add_node(Node::new(id, lon, 0.0)).unwrap();
+    }
+
+    let line = |a: u64, b: u64| {
+        geo_types::LineString::new(vec![
+            geo_types::Coord { x: a as f64, y: 0.0 },
+            geo_types::Coord { x: b as f64, y: 0.0 },
+        ])
+    };
+
+    let e1 = Edge::new(1, 1, 2, line(1, 2), 1000.0).with_frequency(0.01);
+    let e2 = Edge::new(2, 2, 3, line(2, 3), 1000.0).with_frequency(0.01);
+    let e3 = Edge::new(3, 3, 4, line(3, 4), 1000.0);
+
+    network.add_edge(e1, fast_routing::RoutingMode::Walking).unwrap();
+    network.add_edge(e2, fast_routing::RoutingMode::Walking).unwrap();
+    network.add_edge(e3, fast_routing::RoutingMode::Car).unwrap();
+
+    network
+}
+
+#[test]
+fn test_transit_isochrone_respects_max_cost_and_frequency() {
+    let network = transit_chain_network();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
+
+    // Ride cost per edge is 1000m @ 25km/h = 144s; with frequency 0.01 the
+    // expected wait is 1/0.01 = 100s. Node 2 (one edge from node 3) labels
+    // at 144 + 100 = 244s; node 1 (two edges away) labels at
+    // 144 + 244 + 100 = 488s. A 300s budget reaches node 2 but not node 1.
+    let isochrone = IsochroneCalculator::calculate_transit_isochrone(&ch, 3, 300.0).unwrap();
+    assert!(isochrone.expected_cost.contains_key(&3));
+    assert!(isochrone.expected_cost.contains_key(&2));
+    assert!(!isochrone.expected_cost.contains_key(&1));
+}
+
+#[test]
+fn test_transit_isochrone_ignores_edges_without_frequency() {
+    let network = transit_chain_network();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
+
+    // Node 4 is only reachable from node 3 via a plain car edge with no
+    // `frequency`, so even a very generous budget must never reach it.
+    let isochrone = IsochroneCalculator::calculate_transit_isochrone(&ch, 3, 100_000.0).unwrap();
+    assert!(isochrone.expected_cost.contains_key(&1));
+    assert!(!isochrone.expected_cost.contains_key(&4));
+}
+
+#[test]
+fn test_routing_matrix_matches_pairwise_shortest_paths() {
+    let network = DummyNetworkGenerator::create_grid(3, 3, 100.0).unwrap();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
+
+    let sources = [0u64, 4];
+    let targets = [4u64, 8];
+    let matrix = RoutingMatrix::compute(&ch, &sources, &targets).unwrap();
+
+    assert_eq!(matrix.len(), sources.len());
+    for (i, &source) in sources.iter().enumerate() {
+        assert_eq!(matrix[i].len(), targets.len());
+        for (j, &target) in targets.iter().enumerate() {
+            let (expected_cost, _) = ch.shortest_path(source, target, fast_routing::RoutingMode::Car).unwrap().unwrap();
+            assert!((matrix[i][j] - expected_cost).abs() < 1e-6, "matrix[{}][{}] = {}, expected {}", i, j, matrix[i][j], expected_cost);
+        }
+    }
+
+    // source == target is its own closest pair, cost 0.
+    assert_eq!(matrix[1][0], 0.0);
+}
+
+#[test]
+fn test_routing_matrix_rejects_unknown_nodes() {
+    let network = DummyNetworkGenerator::create_simple().unwrap();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
+
+    let result = RoutingMatrix::compute(&ch, &[0], &[999]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_invalid_routing() {
     let network = DummyNetworkGenerator::create_simple().unwrap();
-    let ch = ContractionHierarchy::new(network).unwrap();
+    let ch = ContractionHierarchy::new(network, fast_routing::RoutingMode::Car).unwrap();
     
     // Test routing to non-existent node
-    let result = ch.shortest_path(0, 999);
+    let result = ch.shortest_path(0, 999, fast_routing::RoutingMode::Car);
     assert!(result.is_err());
 }
\ No newline at end of file